@@ -1,30 +1,217 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Local, Timelike};
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
 use walkdir::WalkDir;
 
 use crate::git;
 
+/// Path, commits, lines added, lines removed, changes per month, top contributor,
+/// last modified date and average changes per commit
+pub type ChangedFile = (
+    PathBuf,
+    usize,
+    usize,
+    usize,
+    f64,
+    String,
+    DateTime<FixedOffset>,
+    f64,
+);
+
+/// Share of a file, in percent, that makes an author knowledgeable about it
+/// (the file's primary owner always is)
+const KNOWLEDGE_THRESHOLD: f64 = 25.0;
+
+/// Commits touching more files than this are left out of change coupling,
+/// as bulk changes (formatting, renames, vendoring) couple unrelated files
+const MAX_CHANGESET_SIZE: usize = 30;
+
+/// Minimum number of shared commits for a pair to count as coupled
+const MIN_COUPLING_SUPPORT: usize = 2;
+
+/// Number of blobs kept in the largest blobs in history list
+const LARGEST_BLOB_COUNT: usize = 20;
+
+/// Unmerged branches missing more default branch commits than this count as
+/// long-diverged, as they are increasingly hard to merge
+const MAX_BRANCH_DIVERGENCE: usize = 100;
+
+/// Commit subjects longer than this are flagged, following the usual git convention
+const MAX_SUBJECT_LENGTH: usize = 72;
+
+/// Commit types defined by the Conventional Commits specification and its
+/// widely used Angular convention
+const CONVENTIONAL_TYPES: [&str; 11] = [
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// Dependency lockfiles and other files written by tools rather than people
+const GENERATED_FILE_NAMES: [&str; 17] = [
+    "Cargo.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+    "composer.lock",
+    "Gemfile.lock",
+    "poetry.lock",
+    "Pipfile.lock",
+    "go.sum",
+    "flake.lock",
+    "mix.lock",
+    "pubspec.lock",
+    "Podfile.lock",
+    "packages.lock.json",
+    "gradle.lockfile",
+];
+
+/// Directories holding third-party code checked into the repository
+const VENDORED_DIRECTORIES: [&str; 7] = [
+    "vendor",
+    "vendors",
+    "third_party",
+    "third-party",
+    "thirdparty",
+    "bower_components",
+    "Pods",
+];
+
+/// JavaScript and CSS files whose lines average more characters than this are
+/// treated as minified
+const MINIFIED_LINE_LENGTH: usize = 110;
+
+/// Generated code announces itself within its first lines
+const GENERATED_MARKER_LINES: usize = 10;
+
+/// Options controlling which analyses run and how
+#[derive(Debug, Clone)]
+pub struct AnalysisOptions {
+    pub history: git::HistoryOptions,
+    pub blame: bool,                       // Compute blame-based code ownership
+    pub inactive_months: u32, // Authors without commits for this long count as inactive
+    pub issue_patterns: Vec<Regex>, // Issue and pull request references in commit messages
+    pub stale_days: u32,      // Branches without commits for this long count as stale
+    pub recurse_submodules: bool, // Analyze each checked out submodule as a nested report
+    pub exclude_submodules: bool, // Keep submodule files out of the statistics
+    pub components: Option<ComponentMode>, // Break metrics down per directory or package
+}
+
+impl Default for AnalysisOptions {
+    fn default() -> Self {
+        AnalysisOptions {
+            history: git::HistoryOptions::default(),
+            blame: false,
+            inactive_months: 6,
+            issue_patterns: default_issue_patterns(),
+            stale_days: 90,
+            recurse_submodules: false,
+            exclude_submodules: false,
+            components: None,
+        }
+    }
+}
+
+/// How a repository is split into components for the per-component breakdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentMode {
+    /// Each top-level directory is a component
+    Directories,
+    /// Each detected package (Cargo, npm, pnpm, Go or Maven) is a component
+    Packages,
+}
+
+impl FromStr for ComponentMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "directories" | "directory" => Ok(ComponentMode::Directories),
+            "packages" | "package" => Ok(ComponentMode::Packages),
+            _ => Err(format!(
+                "Unknown component mode: {} (expected directories or packages)",
+                s
+            )),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct RepositoryAnalysis {
     pub repo_path: PathBuf,
     pub file_count: usize,
     pub language_stats: HashMap<String, usize>,
+    pub language_lines: HashMap<String, usize>, // Total lines of the files in each language
     pub total_lines: usize,
     pub code_lines: usize,
     pub comment_lines: usize,
     pub blank_lines: usize,
     pub commit_count: usize,
     pub contributors: Vec<git::Contributor>,
-    pub last_activity: String,
+    pub last_activity: Option<DateTime<FixedOffset>>,
     pub file_extensions: HashMap<String, usize>,
     pub avg_file_size: f64,
     pub largest_files: Vec<(PathBuf, usize)>,
     pub complexity_stats: ComplexityStats,
     pub file_age_stats: FileAgeStats,
     pub duplicate_code: Vec<DuplicateCode>,
-    pub most_changed_files: Vec<(PathBuf, usize, usize, usize, f64, String, String, f64)>,
+    pub most_changed_files: Vec<ChangedFile>,
+    pub ownership: Option<git::BlameOwnership>,
+    pub file_stats: HashMap<PathBuf, git::FileStats>, // History of the files present at HEAD
+    pub bus_factor: BusFactorStats,
+    pub change_coupling: ChangeCoupling,
+    pub hotspots: Vec<Hotspot>,
+    pub activity: ActivityStats,
+    pub commit_messages: CommitMessageStats,
+    pub releases: ReleaseStats,
+    pub commit_sizes: CommitSizeStats,
+    pub blob_history: git::BlobHistory, // Every blob ever committed, including deleted ones
+    pub signatures: SignatureStats,
+    pub branches: BranchHealth,
+    pub merges: MergeStats,
+    pub submodules: Vec<SubmoduleAnalysis>,
+    pub excluded_paths: Vec<PathBuf>, // Directories kept out of file and history statistics
+    pub file_lines: HashMap<PathBuf, usize>, // Total lines of every analyzed file
+    pub components: Vec<Component>,   // Largest first, empty unless a breakdown was requested
+    pub classified_files: Vec<ClassifiedFile>, // Kept out of code metrics, largest first
+}
+
+/// Why a file is kept out of code metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileClass {
+    Vendored,
+    Generated,
+    Minified,
+}
+
+impl FileClass {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FileClass::Vendored => "Vendored",
+            FileClass::Generated => "Generated",
+            FileClass::Minified => "Minified",
+        }
+    }
+}
+
+/// A vendored, generated or minified file
+#[derive(Debug, Clone)]
+pub struct ClassifiedFile {
+    pub path: PathBuf,
+    pub class: FileClass,
+    pub reason: String, // The heuristic or attribute that matched
+    pub lines: usize,
+    pub size_bytes: usize,
+}
+
+#[derive(Debug)]
+pub struct SubmoduleAnalysis {
+    pub submodule: git::SubmoduleInfo,
+    pub analysis: Option<Box<RepositoryAnalysis>>, // Set when analyzed recursively
 }
 
 #[derive(Debug)]
@@ -35,15 +222,269 @@ pub struct ComplexityStats {
     pub avg_function_length: f64,
     pub max_function_length: usize,
     pub long_functions: Vec<(PathBuf, String, usize)>,
+    pub file_complexity: HashMap<PathBuf, usize>, // Complexity of every analyzed file
+}
+
+/// A file that is both complex and frequently changed
+#[derive(Debug, Clone)]
+pub struct Hotspot {
+    pub path: PathBuf,
+    pub commit_count: usize,
+    pub lines_changed: usize,
+    pub change_frequency: f64,
+    pub complexity: usize,
+    pub score: f64, // Normalized churn times normalized complexity, between 0 and 1
 }
 
 #[derive(Debug)]
 pub struct FileAgeStats {
-    pub newest_files: Vec<(PathBuf, String)>,
-    pub oldest_files: Vec<(PathBuf, String)>,
+    pub newest_files: Vec<(PathBuf, DateTime<FixedOffset>)>,
+    pub oldest_files: Vec<(PathBuf, DateTime<FixedOffset>)>,
     pub most_modified_files: Vec<(PathBuf, usize)>,
 }
 
+#[derive(Debug, Default)]
+pub struct BusFactorStats {
+    pub inactive_months: u32,
+    pub repository: BusFactor,
+    pub directories: Vec<(PathBuf, BusFactor)>, // Top-level directories
+    pub orphaned_files: Vec<OrphanedFile>,
+}
+
+/// Minimum number of people whose departure leaves most files without an
+/// active knowledgeable author
+#[derive(Debug, Clone, Default)]
+pub struct BusFactor {
+    pub bus_factor: usize,
+    pub key_people: Vec<String>, // The people removed to reach the bus factor, in order
+    pub file_count: usize,
+}
+
+/// A file whose knowledgeable authors are all inactive
+#[derive(Debug)]
+pub struct OrphanedFile {
+    pub path: PathBuf,
+    pub owners: Vec<String>,
+    pub last_active: Option<DateTime<FixedOffset>>, // Most recent commit by any of the owners
+}
+
+/// Pairs of files and directories that frequently change in the same commit
+#[derive(Debug, Default)]
+pub struct ChangeCoupling {
+    pub file_pairs: Vec<CoupledPair>,
+    pub directory_pairs: Vec<CoupledPair>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CoupledPair {
+    pub first: PathBuf,
+    pub second: PathBuf,
+    pub co_changes: usize,               // Commits changing both
+    pub support: f64,                    // Share of analyzed commits changing both
+    pub confidence_first_to_second: f64, // Share of commits changing first that also change second
+    pub confidence_second_to_first: f64, // Share of commits changing second that also change first
+}
+
+/// Commit activity over time, bucketed in each author's own time zone
+#[derive(Debug, Default)]
+pub struct ActivityStats {
+    pub daily: Vec<ActivityBucket>,   // Periods formatted as YYYY-MM-DD
+    pub weekly: Vec<ActivityBucket>,  // Periods formatted as ISO weeks, YYYY-Www
+    pub monthly: Vec<ActivityBucket>, // Periods formatted as YYYY-MM
+    pub punch_card: [[usize; 24]; 7], // Commits per weekday (Monday first) and hour
+}
+
+#[derive(Debug, Clone)]
+pub struct ActivityBucket {
+    pub period: String,
+    pub commits: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CommitMessageStats {
+    pub commit_count: usize,
+    pub conventional_count: usize, // Subjects following Conventional Commits
+    pub types: Vec<(String, usize)>, // Conventional commit types, most used first
+    pub breaking_changes: usize,   // `type!:` subjects or BREAKING CHANGE footers
+    pub issue_linked_count: usize, // Commits referencing at least one issue
+    pub top_references: Vec<(String, usize)>, // Most referenced issues
+    pub avg_subject_length: f64,
+    pub long_subjects: usize, // Subjects longer than MAX_SUBJECT_LENGTH characters
+    pub with_body: usize,     // Commits with a body besides trailers
+    pub monthly: Vec<MessageBucket>, // Periods formatted as YYYY-MM
+}
+
+#[derive(Debug, Clone)]
+pub struct MessageBucket {
+    pub period: String,
+    pub commits: usize,
+    pub features: usize,
+    pub fixes: usize,
+    pub issue_linked: usize,
+}
+
+impl MessageBucket {
+    /// Features per fix, or None when the period has no fixes
+    pub fn feature_fix_ratio(&self) -> Option<f64> {
+        if self.fixes == 0 {
+            None
+        } else {
+            Some(self.features as f64 / self.fixes as f64)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseStats {
+    pub releases: Vec<Release>,      // Oldest first
+    pub unreleased: Option<Release>, // Commits on HEAD since the latest tag
+    pub cadence: ReleaseCadence,
+}
+
+/// The changes between a tag and the tag before it
+#[derive(Debug, Clone)]
+pub struct Release {
+    pub tag: String, // "HEAD" for unreleased changes
+    pub previous_tag: Option<String>,
+    pub date: DateTime<FixedOffset>,
+    pub annotated: bool,
+    pub commit_count: usize,
+    pub contributors: Vec<(String, usize)>, // Authors by commits, most active first
+    pub files_changed: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub types: Vec<(String, usize)>, // Conventional commit types, "other" for the rest
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseCadence {
+    pub release_count: usize,
+    pub avg_days_between: f64,
+    pub median_days_between: f64,
+    pub shortest_days_between: f64,
+    pub longest_days_between: f64,
+    pub days_since_last_release: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CommitSizeStats {
+    pub files_changed: SizeDistribution,
+    pub lines_added: SizeDistribution,
+    pub lines_removed: SizeDistribution,
+    pub largest_commits: Vec<LargeCommit>, // By lines added plus removed
+}
+
+/// Nearest-rank percentiles of a per-commit measure
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SizeDistribution {
+    pub median: usize,
+    pub p90: usize,
+    pub max: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct LargeCommit {
+    pub id: String,
+    pub author: String,
+    pub subject: String,
+    pub date: DateTime<FixedOffset>,
+    pub files_changed: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SignatureStats {
+    pub commit_count: usize,
+    pub signed_count: usize,
+    pub kinds: Vec<(String, usize)>, // Signature kinds, most used first
+    pub contributors: Vec<(String, usize, usize)>, // Author, signed and total commits
+    pub monthly: Vec<SignatureBucket>, // Periods formatted as YYYY-MM
+}
+
+#[derive(Debug, Clone)]
+pub struct SignatureBucket {
+    pub period: String,
+    pub commits: usize,
+    pub signed: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BranchHealth {
+    pub default_branch: Option<String>,
+    pub stale_days: u32,
+    pub branches: Vec<git::BranchInfo>, // Most recently updated first
+    pub stale: Vec<String>,             // Unmerged branches without recent commits
+    pub merged: Vec<String>,            // Branches already merged into the default branch
+    pub diverged: Vec<String>,          // Unmerged branches far behind the default branch
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MergeStats {
+    pub merge_count: usize,
+    pub merges_per_week: f64, // Over the span of the analyzed history
+    pub avg_branch_lifetime_days: f64, // From merge base to merge
+    pub max_branch_lifetime_days: f64,
+    pub avg_commits_per_branch: f64,
+    pub weekly: Vec<(String, usize)>, // Merges per ISO week, YYYY-Www
+}
+
+/// Where a component comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentKind {
+    Directory,
+    Cargo,
+    Npm,
+    Pnpm,
+    Go,
+    Maven,
+    Root, // Files outside every other component
+}
+
+impl ComponentKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ComponentKind::Directory => "Directory",
+            ComponentKind::Cargo => "Cargo",
+            ComponentKind::Npm => "npm",
+            ComponentKind::Pnpm => "pnpm",
+            ComponentKind::Go => "Go",
+            ComponentKind::Maven => "Maven",
+            ComponentKind::Root => "Root",
+        }
+    }
+}
+
+/// Metrics of one top-level directory or package
+#[derive(Debug, Clone)]
+pub struct Component {
+    pub name: String, // Package name, or the directory name
+    pub path: PathBuf,
+    pub kind: ComponentKind,
+    pub file_count: usize,
+    pub total_lines: usize,
+    pub language_lines: HashMap<String, usize>,
+    pub avg_complexity: f64,
+    pub max_complexity: usize,
+    pub commit_count: usize, // Commits touching at least one file of the component
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub contributors: Vec<(String, usize)>, // Authors by commits, most active first
+    pub complex_files: Vec<(PathBuf, usize)>, // Most complex files first
+    pub changed_files: Vec<(PathBuf, usize)>, // Most frequently changed files first
+}
+
+/// The parts of a commit message relevant to message statistics
+#[derive(Debug, Clone)]
+struct ParsedMessage {
+    subject_length: usize,
+    commit_type: Option<String>, // Conventional commit type, lowercased
+    breaking: bool,
+    has_body: bool,
+}
+
 #[derive(Debug)]
 pub struct DuplicateCode {
     pub files: Vec<PathBuf>,
@@ -51,7 +492,10 @@ pub struct DuplicateCode {
     pub similarity: f64,
 }
 
-pub fn analyze_repository(repo_path: &Path, history_depth: usize) -> Result<RepositoryAnalysis> {
+pub fn analyze_repository(
+    repo_path: &Path,
+    options: &AnalysisOptions,
+) -> Result<RepositoryAnalysis> {
     println!("Starting repository analysis...");
     println!("Repository path: {}", repo_path.display());
 
@@ -60,13 +504,14 @@ pub fn analyze_repository(repo_path: &Path, history_depth: usize) -> Result<Repo
         repo_path: repo_path.to_path_buf(),
         file_count: 0,
         language_stats: HashMap::new(),
+        language_lines: HashMap::new(),
         total_lines: 0,
         code_lines: 0,
         comment_lines: 0,
         blank_lines: 0,
         commit_count: 0,
         contributors: Vec::new(),
-        last_activity: String::new(),
+        last_activity: None,
         file_extensions: HashMap::new(),
         avg_file_size: 0.0,
         largest_files: Vec::new(),
@@ -77,6 +522,7 @@ pub fn analyze_repository(repo_path: &Path, history_depth: usize) -> Result<Repo
             avg_function_length: 0.0,
             max_function_length: 0,
             long_functions: Vec::new(),
+            file_complexity: HashMap::new(),
         },
         file_age_stats: FileAgeStats {
             newest_files: Vec::new(),
@@ -85,130 +531,365 @@ pub fn analyze_repository(repo_path: &Path, history_depth: usize) -> Result<Repo
         },
         duplicate_code: Vec::new(),
         most_changed_files: Vec::new(),
+        ownership: None,
+        file_stats: HashMap::new(),
+        bus_factor: BusFactorStats::default(),
+        change_coupling: ChangeCoupling::default(),
+        hotspots: Vec::new(),
+        activity: ActivityStats::default(),
+        commit_messages: CommitMessageStats::default(),
+        releases: ReleaseStats::default(),
+        commit_sizes: CommitSizeStats::default(),
+        blob_history: git::BlobHistory::default(),
+        signatures: SignatureStats::default(),
+        branches: BranchHealth::default(),
+        merges: MergeStats::default(),
+        submodules: Vec::new(),
+        excluded_paths: Vec::new(),
+        file_lines: HashMap::new(),
+        components: Vec::new(),
+        classified_files: Vec::new(),
     };
 
+    // List submodules, keeping their files out of the statistics if requested
+    let submodules = git::list_submodules(repo_path).context("Failed to list submodules")?;
+    if options.exclude_submodules {
+        analysis.excluded_paths = submodules
+            .iter()
+            .map(|submodule| submodule.path.clone())
+            .collect();
+    }
+
+    // Find the components metrics are broken down by
+    if let Some(mode) = options.components {
+        analysis.components = detect_components(repo_path, mode);
+    }
+
     // Analyze files
-    analyze_files(repo_path, &mut analysis)?;
+    analyze_files(repo_path, &mut analysis, &options.history.path_filter)?;
 
     // Analyze git history
-    analyze_git_history(repo_path, &mut analysis, history_depth)?;
+    let commits = analyze_git_history(repo_path, &mut analysis, options)?;
+
+    // Analyze releases
+    analyze_releases(repo_path, &mut analysis, &options.history, &commits)?;
+
+    // Analyze branch health
+    analyze_branch_health(repo_path, &mut analysis, options.stale_days)?;
+
+    // Find large blobs anywhere in history
+    println!("Analyzing history size...");
+    analysis.blob_history =
+        git::analyze_blobs(repo_path, LARGEST_BLOB_COUNT, &options.history.path_filter)
+            .context("Failed to analyze blobs in history")?;
+
+    // Analyze code ownership
+    if options.blame {
+        analyze_ownership(repo_path, &mut analysis, &options.history)?;
+    }
+
+    // Analyze knowledge concentration
+    analyze_bus_factor(repo_path, &mut analysis, options.inactive_months);
 
     // Analyze code complexity
-    analyze_code_complexity(repo_path, &mut analysis)?;
+    analyze_code_complexity(repo_path, &mut analysis, &options.history.path_filter)?;
+
+    // Combine churn and complexity
+    analyze_hotspots(&mut analysis);
+
+    // Aggregate metrics per component
+    if !analysis.components.is_empty() {
+        summarize_components(&mut analysis);
+    }
 
     // Find duplicate code
-    find_duplicate_code(repo_path, &mut analysis)?;
+    find_duplicate_code(repo_path, &mut analysis, &options.history.path_filter)?;
+
+    // Analyze submodules as nested reports
+    analysis.submodules = analyze_submodules(submodules, options);
 
     println!("Analysis complete!");
     Ok(analysis)
 }
 
-fn analyze_files(repo_path: &Path, analysis: &mut RepositoryAnalysis) -> Result<()> {
-    println!("Analyzing files...");
+fn analyze_submodules(
+    submodules: Vec<git::SubmoduleInfo>,
+    options: &AnalysisOptions,
+) -> Vec<SubmoduleAnalysis> {
+    // Revision selections name commits of the superproject and path globs are
+    // relative to its root, so submodules are analyzed from their own HEAD in full
+    let nested_options = AnalysisOptions {
+        history: git::HistoryOptions {
+            rev_range: None,
+            branches: Vec::new(),
+            all_refs: false,
+            path_filter: git::PathFilter::default(),
+            ..options.history.clone()
+        },
+        ..options.clone()
+    };
 
-    let ignore_patterns = vec![
-        Regex::new(r"\.git/").unwrap(),
-        Regex::new(r"node_modules/").unwrap(),
-        Regex::new(r"target/").unwrap(),
-        Regex::new(r"\.DS_Store").unwrap(),
-        Regex::new(r"\.idea/").unwrap(),
-        Regex::new(r"\.vscode/").unwrap(),
-        Regex::new(r"dist/").unwrap(),
-        Regex::new(r"build/").unwrap(),
-        Regex::new(r"\.cache/").unwrap(),
-    ];
-
-    for entry in WalkDir::new(repo_path)
+    submodules
         .into_iter()
-        .filter_entry(|e| !is_ignored(e.path(), &ignore_patterns))
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-    {
+        .map(|submodule| {
+            let analysis = if options.recurse_submodules && submodule.initialized {
+                println!("Analyzing submodule {}...", submodule.name);
+                match analyze_repository(&submodule.path, &nested_options) {
+                    Ok(analysis) => Some(Box::new(analysis)),
+                    Err(e) => {
+                        println!(
+                            "Warning: Failed to analyze submodule {}: {}",
+                            submodule.name, e
+                        );
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+            SubmoduleAnalysis {
+                submodule,
+                analysis,
+            }
+        })
+        .collect()
+}
+
+fn analyze_files(
+    repo_path: &Path,
+    analysis: &mut RepositoryAnalysis,
+    path_filter: &git::PathFilter,
+) -> Result<()> {
+    println!("Analyzing files...");
+
+    let attributes = git::LinguistAttributes::open(repo_path);
+    let generated_patterns = generated_path_patterns();
+
+    let excluded_paths = analysis.excluded_paths.clone();
+    let no_skipped_files = HashSet::new();
+    for entry in source_files(repo_path, &excluded_paths, &no_skipped_files, path_filter) {
+        let content = std::fs::read_to_string(entry.path()).ok();
+        let file_size = entry
+            .metadata()
+            .map(|metadata| metadata.len() as usize)
+            .ok();
+
+        // Vendored, generated and minified files are listed on their own
+        let relative = entry.path().strip_prefix(repo_path).unwrap_or(entry.path());
+        if let Some((class, reason)) = classify_file(
+            relative,
+            content.as_deref(),
+            &attributes,
+            &generated_patterns,
+        ) {
+            analysis.classified_files.push(ClassifiedFile {
+                path: entry.path().to_path_buf(),
+                class,
+                reason,
+                lines: content
+                    .as_deref()
+                    .map_or(0, |content| content.lines().count()),
+                size_bytes: file_size.unwrap_or(0),
+            });
+            continue;
+        }
+
         analysis.file_count += 1;
 
         // Get file size
-        if let Ok(metadata) = entry.metadata() {
-            let file_size = metadata.len() as usize;
+        if let Some(file_size) = file_size {
             analysis
                 .largest_files
                 .push((entry.path().to_path_buf(), file_size));
         }
 
         // Get file extension
+        let mut file_language = None;
         if let Some(extension) = entry.path().extension() {
             if let Some(ext_str) = extension.to_str() {
                 let ext = ext_str.to_lowercase();
                 *analysis.file_extensions.entry(ext.clone()).or_insert(0) += 1;
 
-                // Map extensions to languages
-                let language = match ext.as_str() {
-                    "rs" => "Rust",
-                    "js" => "JavaScript",
-                    "ts" => "TypeScript",
-                    "jsx" => "React",
-                    "tsx" => "React",
-                    "py" => "Python",
-                    "java" => "Java",
-                    "c" | "h" => "C",
-                    "cpp" | "hpp" => "C++",
-                    "go" => "Go",
-                    "rb" => "Ruby",
-                    "php" => "PHP",
-                    "html" => "HTML",
-                    "css" => "CSS",
-                    "scss" | "sass" => "SASS",
-                    "md" => "Markdown",
-                    "json" => "JSON",
-                    "yml" | "yaml" => "YAML",
-                    "toml" => "TOML",
-                    "sh" | "bash" => "Shell",
-                    "sql" => "SQL",
-                    "swift" => "Swift",
-                    "kt" | "kts" => "Kotlin",
-                    "dart" => "Dart",
-                    "ex" | "exs" => "Elixir",
-                    "hs" => "Haskell",
-                    "clj" => "Clojure",
-                    "fs" => "F#",
-                    "vue" => "Vue",
-                    "svelte" => "Svelte",
-                    "xml" => "XML",
-                    "gradle" => "Gradle",
-                    "tf" | "tfvars" => "Terraform",
-                    "proto" => "Protocol Buffers",
-                    "graphql" | "gql" => "GraphQL",
-                    "r" => "R",
-                    "lua" => "Lua",
-                    "pl" | "pm" => "Perl",
-                    "cs" => "C#",
-                    "vb" => "Visual Basic",
-                    "scala" => "Scala",
-                    "groovy" => "Groovy",
-                    "m" => "Objective-C",
-                    "mm" => "Objective-C++",
-                    _ => "Other",
-                };
+                let language = language_for_extension(&ext);
 
                 *analysis
                     .language_stats
                     .entry(language.to_string())
                     .or_insert(0) += 1;
+                file_language = Some(language);
             }
         }
 
         // Count lines and analyze code
-        if let Ok(content) = std::fs::read_to_string(entry.path()) {
-            let (total, code, comment, blank) = count_line_types(&content, entry.path());
+        if let Some(content) = &content {
+            let (total, code, comment, blank) = count_line_types(content, entry.path());
             analysis.total_lines += total;
             analysis.code_lines += code;
             analysis.comment_lines += comment;
             analysis.blank_lines += blank;
+            if let Some(language) = file_language {
+                *analysis
+                    .language_lines
+                    .entry(language.to_string())
+                    .or_insert(0) += total;
+            }
+            analysis
+                .file_lines
+                .insert(entry.path().to_path_buf(), total);
         }
     }
 
+    analysis.classified_files.sort_by(|a, b| {
+        b.lines
+            .cmp(&a.lines)
+            .then(b.size_bytes.cmp(&a.size_bytes))
+            .then(a.path.cmp(&b.path))
+    });
+
     Ok(())
 }
 
+/// Classify a file as vendored, generated or minified. Linguist attributes in
+/// .gitattributes take precedence over the heuristics, in both directions
+fn classify_file(
+    relative_path: &Path,
+    content: Option<&str>,
+    attributes: &git::LinguistAttributes,
+    generated_patterns: &[Regex],
+) -> Option<(FileClass, String)> {
+    let vendored_attribute = attributes.get(relative_path, "linguist-vendored");
+    let generated_attribute = attributes.get(relative_path, "linguist-generated");
+    if vendored_attribute == Some(true) {
+        return Some((FileClass::Vendored, "linguist-vendored".to_string()));
+    }
+    if generated_attribute == Some(true) {
+        return Some((FileClass::Generated, "linguist-generated".to_string()));
+    }
+
+    if vendored_attribute.is_none() {
+        let vendored_dir = relative_path
+            .parent()
+            .into_iter()
+            .flat_map(|parent| parent.components())
+            .map(|component| component.as_os_str().to_string_lossy())
+            .find(|name| VENDORED_DIRECTORIES.contains(&name.as_ref()));
+        if let Some(dir) = vendored_dir {
+            return Some((FileClass::Vendored, format!("{}/ directory", dir)));
+        }
+    }
+
+    if generated_attribute.is_some() {
+        return None;
+    }
+
+    let file_name = relative_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if GENERATED_FILE_NAMES.contains(&file_name.as_str()) {
+        return Some((FileClass::Generated, "lockfile".to_string()));
+    }
+    if generated_patterns
+        .iter()
+        .any(|pattern| pattern.is_match(&file_name))
+    {
+        return Some((FileClass::Generated, "generated file name".to_string()));
+    }
+
+    let ext = relative_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_default();
+    let minifiable = matches!(ext.as_str(), "js" | "mjs" | "cjs" | "css");
+    if minifiable && (file_name.ends_with(".min.js") || file_name.ends_with(".min.css")) {
+        return Some((FileClass::Minified, ".min file name".to_string()));
+    }
+
+    let content = content?;
+    let marker = content.lines().take(GENERATED_MARKER_LINES).any(|line| {
+        line.contains("@generated")
+            || line.contains("DO NOT EDIT")
+            || line.contains("<auto-generated")
+    });
+    if marker {
+        return Some((FileClass::Generated, "generated marker".to_string()));
+    }
+
+    if minifiable {
+        let line_count = content.lines().count();
+        if line_count > 0 && content.len() / line_count > MINIFIED_LINE_LENGTH {
+            return Some((FileClass::Minified, "long lines".to_string()));
+        }
+    }
+
+    None
+}
+
+/// File names of code emitted by protobuf, gRPC and other generators
+fn generated_path_patterns() -> Vec<Regex> {
+    vec![
+        Regex::new(r"\.pb\.(go|cc|h|swift|rs|gw\.go)$").unwrap(),
+        Regex::new(r"_pb2(_grpc)?\.pyi?$").unwrap(),
+        Regex::new(r"_pb\.(js|d\.ts)$").unwrap(),
+        Regex::new(r"\.g\.dart$").unwrap(),
+        Regex::new(r"\.(designer|generated)\.\w+$").unwrap(),
+        Regex::new(r"_generated\.\w+$").unwrap(),
+        Regex::new(r"\.(js|css)\.map$").unwrap(),
+    ]
+}
+
+/// Map a lowercased file extension to the language it is written in
+fn language_for_extension(ext: &str) -> &'static str {
+    match ext {
+        "rs" => "Rust",
+        "js" => "JavaScript",
+        "ts" => "TypeScript",
+        "jsx" => "React",
+        "tsx" => "React",
+        "py" => "Python",
+        "java" => "Java",
+        "c" | "h" => "C",
+        "cpp" | "hpp" => "C++",
+        "go" => "Go",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "html" => "HTML",
+        "css" => "CSS",
+        "scss" | "sass" => "SASS",
+        "md" => "Markdown",
+        "json" => "JSON",
+        "yml" | "yaml" => "YAML",
+        "toml" => "TOML",
+        "sh" | "bash" => "Shell",
+        "sql" => "SQL",
+        "swift" => "Swift",
+        "kt" | "kts" => "Kotlin",
+        "dart" => "Dart",
+        "ex" | "exs" => "Elixir",
+        "hs" => "Haskell",
+        "clj" => "Clojure",
+        "fs" => "F#",
+        "vue" => "Vue",
+        "svelte" => "Svelte",
+        "xml" => "XML",
+        "gradle" => "Gradle",
+        "tf" | "tfvars" => "Terraform",
+        "proto" => "Protocol Buffers",
+        "graphql" | "gql" => "GraphQL",
+        "r" => "R",
+        "lua" => "Lua",
+        "pl" | "pm" => "Perl",
+        "cs" => "C#",
+        "vb" => "Visual Basic",
+        "scala" => "Scala",
+        "groovy" => "Groovy",
+        "m" => "Objective-C",
+        "mm" => "Objective-C++",
+        _ => "Other",
+    }
+}
+
 fn count_line_types(content: &str, path: &Path) -> (usize, usize, usize, usize) {
     let mut total_lines = 0;
     let mut code_lines = 0;
@@ -251,11 +932,9 @@ fn count_line_types(content: &str, path: &Path) -> (usize, usize, usize, usize)
                         return true;
                     }
                 }
-                "py" | "rb" | "sh" => {
+                "py" | "rb" | "sh" if line.trim().starts_with("#") => {
                     // Python/Ruby/Shell comments
-                    if line.trim().starts_with("#") {
-                        return true;
-                    }
+                    return true;
                 }
                 "html" | "xml" => {
                     // HTML/XML comments
@@ -290,85 +969,954 @@ fn count_line_types(content: &str, path: &Path) -> (usize, usize, usize, usize)
         }
     }
 
-    (total_lines, code_lines, comment_lines, blank_lines)
+    (total_lines, code_lines, comment_lines, blank_lines)
+}
+
+/// Walks the history and fills in the history-based statistics, returning the
+/// commits that were analyzed so later steps do not have to walk them again
+fn analyze_git_history(
+    repo_path: &Path,
+    analysis: &mut RepositoryAnalysis,
+    options: &AnalysisOptions,
+) -> Result<Vec<git::CommitRecord>> {
+    println!("Analyzing git history...");
+
+    let history = git::analyze_git_repo_extended(repo_path, &options.history)
+        .context("Failed to analyze git repository")?;
+
+    // Files deleted by their most recent change are no longer part of the
+    // repository, and vendored or generated files do not count as churn
+    let classified = classified_paths(analysis);
+    let file_stats: HashMap<PathBuf, git::FileStats> = history
+        .file_stats
+        .into_iter()
+        .filter(|(path, stats)| {
+            !stats.deleted
+                && !is_excluded(path, &analysis.excluded_paths)
+                && !classified.contains(path)
+        })
+        .collect();
+
+    analysis.commit_count = history.commit_count;
+    analysis.contributors = history.contributors;
+    analysis.last_activity = history.last_activity;
+
+    analysis.activity = calculate_activity(&history.commits);
+    analysis.commit_messages = calculate_commit_messages(&history.commits, &options.issue_patterns);
+    analysis.commit_sizes = calculate_commit_sizes(&history.commits);
+    analysis.signatures = calculate_signatures(&history.commits);
+    analysis.merges = calculate_merges(&history.commits);
+    analyze_component_history(&mut analysis.components, &history.commits, &file_stats);
+
+    // Change coupling between files still present at HEAD
+    let changesets: Vec<Vec<PathBuf>> = history
+        .commits
+        .iter()
+        .map(|commit| {
+            commit
+                .files
+                .iter()
+                .filter(|path| file_stats.contains_key(*path))
+                .cloned()
+                .collect()
+        })
+        .collect();
+
+    let directory_changesets: Vec<Vec<PathBuf>> = changesets
+        .iter()
+        .map(|files| {
+            let directories: HashSet<PathBuf> = files
+                .iter()
+                .filter_map(|path| path.parent().map(|dir| dir.to_path_buf()))
+                .collect();
+            directories.into_iter().collect()
+        })
+        .collect();
+
+    analysis.change_coupling = ChangeCoupling {
+        file_pairs: calculate_change_coupling(&changesets, history.commit_count),
+        directory_pairs: calculate_change_coupling(&directory_changesets, history.commit_count),
+    };
+
+    // Process file age stats
+    let mut newest_files: Vec<(PathBuf, DateTime<FixedOffset>)> = file_stats
+        .iter()
+        .map(|(path, stats)| (path.clone(), stats.first_commit_date))
+        .collect();
+    newest_files.sort_by_key(|(_, date)| std::cmp::Reverse(*date));
+    analysis.file_age_stats.newest_files = newest_files.into_iter().take(10).collect();
+
+    let mut oldest_files: Vec<(PathBuf, DateTime<FixedOffset>)> = file_stats
+        .iter()
+        .map(|(path, stats)| (path.clone(), stats.first_commit_date))
+        .collect();
+    oldest_files.sort_by_key(|(_, date)| *date);
+    analysis.file_age_stats.oldest_files = oldest_files.into_iter().take(10).collect();
+
+    let mut most_modified_files: Vec<(PathBuf, usize)> = file_stats
+        .iter()
+        .map(|(path, stats)| (path.clone(), stats.commit_count))
+        .collect();
+    most_modified_files.sort_by(|(_, a), (_, b)| b.cmp(a));
+    analysis.file_age_stats.most_modified_files =
+        most_modified_files.into_iter().take(10).collect();
+
+    // Create most changed files info for the report
+    let mut most_changed_files = Vec::new();
+    for (path, stats) in file_stats.iter() {
+        // Find top contributor for this file
+        let mut top_contributor = String::from("Unknown");
+        let mut max_commits = 0;
+
+        for (author, commit_count) in &stats.author_contributions {
+            if *commit_count > max_commits {
+                max_commits = *commit_count;
+                top_contributor = author.clone();
+            }
+        }
+
+        most_changed_files.push((
+            path.clone(),
+            stats.commit_count,
+            stats.lines_added,
+            stats.lines_removed,
+            stats.change_frequency,
+            top_contributor,
+            stats.last_commit_date,
+            stats.avg_changes_per_commit,
+        ));
+    }
+
+    // Sort by change frequency
+    most_changed_files.sort_by(|(_, _, _, _, a, _, _, _), (_, _, _, _, b, _, _, _)| {
+        b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    // Store the top 10 most changed files
+    analysis.most_changed_files = most_changed_files.into_iter().take(10).collect();
+    analysis.file_stats = file_stats;
+
+    Ok(history.commits)
+}
+
+fn calculate_activity(commits: &[git::CommitRecord]) -> ActivityStats {
+    let mut daily: HashMap<String, ActivityBucket> = HashMap::new();
+    let mut weekly: HashMap<String, ActivityBucket> = HashMap::new();
+    let mut monthly: HashMap<String, ActivityBucket> = HashMap::new();
+    let mut punch_card = [[0; 24]; 7];
+
+    for commit in commits {
+        let time = commit.author_time;
+
+        for (buckets, period) in [
+            (&mut daily, time.format("%Y-%m-%d").to_string()),
+            (&mut weekly, time.format("%G-W%V").to_string()),
+            (&mut monthly, time.format("%Y-%m").to_string()),
+        ] {
+            let bucket = buckets
+                .entry(period.clone())
+                .or_insert_with(|| ActivityBucket {
+                    period,
+                    commits: 0,
+                    lines_added: 0,
+                    lines_removed: 0,
+                });
+            bucket.commits += 1;
+            bucket.lines_added += commit.lines_added;
+            bucket.lines_removed += commit.lines_removed;
+        }
+
+        punch_card[time.weekday().num_days_from_monday() as usize][time.hour() as usize] += 1;
+    }
+
+    let sorted = |buckets: HashMap<String, ActivityBucket>| {
+        let mut buckets: Vec<ActivityBucket> = buckets.into_values().collect();
+        buckets.sort_by(|a, b| a.period.cmp(&b.period));
+        buckets
+    };
+
+    ActivityStats {
+        daily: sorted(daily),
+        weekly: sorted(weekly),
+        monthly: sorted(monthly),
+        punch_card,
+    }
+}
+
+fn calculate_merges(commits: &[git::CommitRecord]) -> MergeStats {
+    let merges: Vec<&git::MergeRecord> = commits
+        .iter()
+        .filter_map(|commit| commit.merge.as_ref())
+        .collect();
+    if merges.is_empty() {
+        return MergeStats::default();
+    }
+
+    let lifetimes: Vec<f64> = merges
+        .iter()
+        .map(|merge| {
+            merge
+                .merge_time
+                .signed_duration_since(merge.merge_base_time)
+                .num_seconds()
+                .max(0) as f64
+                / 86400.0
+        })
+        .collect();
+
+    // Commits are newest first; a span under a week counts as one week
+    let span_weeks = match (commits.last(), commits.first()) {
+        (Some(oldest), Some(newest)) => {
+            newest
+                .author_time
+                .signed_duration_since(oldest.author_time)
+                .num_seconds() as f64
+                / (7.0 * 86400.0)
+        }
+        _ => 0.0,
+    };
+
+    let mut weekly: HashMap<String, usize> = HashMap::new();
+    for merge in &merges {
+        *weekly
+            .entry(merge.merge_time.format("%G-W%V").to_string())
+            .or_insert(0) += 1;
+    }
+    let mut weekly: Vec<(String, usize)> = weekly.into_iter().collect();
+    weekly.sort();
+
+    MergeStats {
+        merge_count: merges.len(),
+        merges_per_week: merges.len() as f64 / span_weeks.max(1.0),
+        avg_branch_lifetime_days: lifetimes.iter().sum::<f64>() / lifetimes.len() as f64,
+        max_branch_lifetime_days: lifetimes.iter().cloned().fold(0.0, f64::max),
+        avg_commits_per_branch: merges
+            .iter()
+            .map(|merge| merge.branch_commits)
+            .sum::<usize>() as f64
+            / merges.len() as f64,
+        weekly,
+    }
+}
+
+fn calculate_signatures(commits: &[git::CommitRecord]) -> SignatureStats {
+    let mut kinds: HashMap<&str, usize> = HashMap::new();
+    let mut contributors: HashMap<&str, (usize, usize)> = HashMap::new();
+    let mut monthly: HashMap<String, SignatureBucket> = HashMap::new();
+    let mut signed_count = 0;
+
+    for commit in commits {
+        let signed = commit.signature.is_some();
+        if let Some(kind) = commit.signature {
+            signed_count += 1;
+            *kinds.entry(kind.name()).or_insert(0) += 1;
+        }
+
+        let counts = contributors.entry(&commit.author).or_insert((0, 0));
+        counts.1 += 1;
+        if signed {
+            counts.0 += 1;
+        }
+
+        let period = commit.author_time.format("%Y-%m").to_string();
+        let bucket = monthly
+            .entry(period.clone())
+            .or_insert_with(|| SignatureBucket {
+                period,
+                commits: 0,
+                signed: 0,
+            });
+        bucket.commits += 1;
+        if signed {
+            bucket.signed += 1;
+        }
+    }
+
+    let mut kinds: Vec<(String, usize)> = kinds
+        .into_iter()
+        .map(|(kind, count)| (kind.to_string(), count))
+        .collect();
+    kinds.sort_by(|(a_kind, a), (b_kind, b)| b.cmp(a).then(a_kind.cmp(b_kind)));
+
+    let mut contributors: Vec<(String, usize, usize)> = contributors
+        .into_iter()
+        .map(|(author, (signed, total))| (author.to_string(), signed, total))
+        .collect();
+    contributors.sort_by(|(a_name, _, a), (b_name, _, b)| b.cmp(a).then(a_name.cmp(b_name)));
+
+    let mut monthly: Vec<SignatureBucket> = monthly.into_values().collect();
+    monthly.sort_by(|a, b| a.period.cmp(&b.period));
+
+    SignatureStats {
+        commit_count: commits.len(),
+        signed_count,
+        kinds,
+        contributors,
+        monthly,
+    }
+}
+
+fn calculate_commit_sizes(commits: &[git::CommitRecord]) -> CommitSizeStats {
+    let distribution = |measure: fn(&git::CommitRecord) -> usize| {
+        let mut values: Vec<usize> = commits.iter().map(measure).collect();
+        values.sort_unstable();
+        SizeDistribution {
+            median: percentile(&values, 50.0),
+            p90: percentile(&values, 90.0),
+            max: values.last().copied().unwrap_or(0),
+        }
+    };
+
+    let mut largest: Vec<&git::CommitRecord> = commits.iter().collect();
+    largest.sort_by_key(|commit| std::cmp::Reverse(commit.lines_added + commit.lines_removed));
+
+    CommitSizeStats {
+        files_changed: distribution(|commit| commit.files.len()),
+        lines_added: distribution(|commit| commit.lines_added),
+        lines_removed: distribution(|commit| commit.lines_removed),
+        largest_commits: largest
+            .into_iter()
+            .take(10)
+            .map(|commit| LargeCommit {
+                id: commit.id.clone(),
+                author: commit.author.clone(),
+                subject: commit.message.lines().next().unwrap_or("").to_string(),
+                date: commit.author_time,
+                files_changed: commit.files.len(),
+                lines_added: commit.lines_added,
+                lines_removed: commit.lines_removed,
+            })
+            .collect(),
+    }
+}
+
+/// Nearest-rank percentile of sorted values, 0 when there are none
+fn percentile(sorted: &[usize], percent: f64) -> usize {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn analyze_branch_health(
+    repo_path: &Path,
+    analysis: &mut RepositoryAnalysis,
+    stale_days: u32,
+) -> Result<()> {
+    println!("Analyzing branches...");
+
+    let (default_branch, mut branches) =
+        git::analyze_branches(repo_path).context("Failed to analyze branches")?;
+    branches.sort_by_key(|branch| std::cmp::Reverse(branch.last_commit));
+
+    let cutoff = Local::now().fixed_offset() - Duration::days(stale_days as i64);
+    let unmerged = || branches.iter().filter(|b| !b.is_default && !b.merged);
+
+    analysis.branches = BranchHealth {
+        default_branch,
+        stale_days,
+        stale: unmerged()
+            .filter(|b| b.last_commit < cutoff)
+            .map(|b| b.name.clone())
+            .collect(),
+        merged: branches
+            .iter()
+            .filter(|b| !b.is_default && b.merged)
+            .map(|b| b.name.clone())
+            .collect(),
+        diverged: unmerged()
+            .filter(|b| b.behind > MAX_BRANCH_DIVERGENCE)
+            .map(|b| b.name.clone())
+            .collect(),
+        branches,
+    };
+
+    Ok(())
+}
+
+fn analyze_releases(
+    repo_path: &Path,
+    analysis: &mut RepositoryAnalysis,
+    history_options: &git::HistoryOptions,
+    commits: &[git::CommitRecord],
+) -> Result<()> {
+    println!("Analyzing releases...");
+
+    let tags = git::list_tags(repo_path).context("Failed to list tags")?;
+
+    // Each release covers the commits reachable from its tag but not from the
+    // previous one, walked on their own so the path, date and revision scope of
+    // the main analysis does not change release sizes. Commits of the main
+    // walk are reused when it kept every file and commit; the rest are diffed
+    let unscoped = git::HistoryOptions {
+        depth: 0,
+        since: None,
+        until: None,
+        rev_range: None,
+        branches: Vec::new(),
+        all_refs: false,
+        path_filter: git::PathFilter::default(),
+        ..history_options.clone()
+    };
+    let records: HashMap<&str, &git::CommitRecord> =
+        if history_options.path_filter.is_active() || history_options.exclude_bots {
+            HashMap::new()
+        } else {
+            commits
+                .iter()
+                .map(|commit| (commit.id.as_str(), commit))
+                .collect()
+        };
+    let interval_commits =
+        |rev_range: Option<String>, branches: Vec<String>| -> Result<Vec<git::CommitRecord>> {
+            let options = git::HistoryOptions {
+                rev_range,
+                branches,
+                ..unscoped.clone()
+            };
+            let ids = git::list_commit_ids(repo_path, &options)?;
+            let missing: Vec<String> = ids
+                .iter()
+                .filter(|id| !records.contains_key(id.as_str()))
+                .cloned()
+                .collect();
+            let mut diffed: HashMap<String, git::CommitRecord> =
+                git::commit_records(repo_path, &missing, &unscoped)?
+                    .into_iter()
+                    .map(|commit| (commit.id.clone(), commit))
+                    .collect();
+            Ok(ids
+                .iter()
+                .filter_map(|id| match records.get(id.as_str()) {
+                    Some(commit) => Some((*commit).clone()),
+                    None => diffed.remove(id),
+                })
+                .collect())
+        };
+
+    let mut releases = Vec::new();
+    let mut previous: Option<&git::Tag> = None;
+    for tag in &tags {
+        let release_commits = match previous {
+            Some(previous) => interval_commits(
+                Some(format!("{}..{}", previous.commit_id, tag.commit_id)),
+                Vec::new(),
+            ),
+            None => interval_commits(None, vec![tag.commit_id.clone()]),
+        }
+        .with_context(|| format!("Failed to analyze release {}", tag.name))?;
+
+        releases.push(summarize_release(
+            &tag.name,
+            previous.map(|previous| previous.name.clone()),
+            tag.date,
+            tag.annotated,
+            &release_commits,
+        ));
+        previous = Some(tag);
+    }
+
+    let unreleased = match previous {
+        Some(latest) => {
+            let unreleased_commits =
+                interval_commits(Some(format!("{}..HEAD", latest.commit_id)), Vec::new())
+                    .context("Failed to analyze unreleased changes")?;
+            unreleased_commits.first().map(|head| {
+                summarize_release(
+                    "HEAD",
+                    Some(latest.name.clone()),
+                    head.author_time,
+                    false,
+                    &unreleased_commits,
+                )
+            })
+        }
+        None => None,
+    };
+
+    analysis.releases = ReleaseStats {
+        cadence: calculate_release_cadence(&tags),
+        releases,
+        unreleased,
+    };
+
+    Ok(())
+}
+
+fn summarize_release(
+    tag: &str,
+    previous_tag: Option<String>,
+    date: DateTime<FixedOffset>,
+    annotated: bool,
+    commits: &[git::CommitRecord],
+) -> Release {
+    let mut contributors: HashMap<&str, usize> = HashMap::new();
+    let mut types: HashMap<String, usize> = HashMap::new();
+    let mut files: HashSet<&PathBuf> = HashSet::new();
+
+    for commit in commits {
+        *contributors.entry(&commit.author).or_insert(0) += 1;
+        let commit_type = parse_commit_message(&commit.message)
+            .commit_type
+            .unwrap_or_else(|| "other".to_string());
+        *types.entry(commit_type).or_insert(0) += 1;
+        files.extend(commit.files.iter());
+    }
+
+    let mut contributors: Vec<(String, usize)> = contributors
+        .into_iter()
+        .map(|(author, count)| (author.to_string(), count))
+        .collect();
+    contributors.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then(a_name.cmp(b_name)));
+
+    let mut types: Vec<(String, usize)> = types.into_iter().collect();
+    types.sort_by(|(a_type, a), (b_type, b)| b.cmp(a).then(a_type.cmp(b_type)));
+
+    Release {
+        tag: tag.to_string(),
+        previous_tag,
+        date,
+        annotated,
+        commit_count: commits.len(),
+        contributors,
+        files_changed: files.len(),
+        lines_added: commits.iter().map(|commit| commit.lines_added).sum(),
+        lines_removed: commits.iter().map(|commit| commit.lines_removed).sum(),
+        types,
+    }
+}
+
+fn calculate_release_cadence(tags: &[git::Tag]) -> ReleaseCadence {
+    let mut cadence = ReleaseCadence {
+        release_count: tags.len(),
+        days_since_last_release: tags.last().map(|latest| {
+            Local::now()
+                .fixed_offset()
+                .signed_duration_since(latest.date)
+                .num_seconds() as f64
+                / 86400.0
+        }),
+        ..ReleaseCadence::default()
+    };
+
+    let mut intervals: Vec<f64> = tags
+        .windows(2)
+        .map(|pair| {
+            pair[1]
+                .date
+                .signed_duration_since(pair[0].date)
+                .num_seconds() as f64
+                / 86400.0
+        })
+        .collect();
+    if intervals.is_empty() {
+        return cadence;
+    }
+
+    intervals.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let middle = intervals.len() / 2;
+    cadence.median_days_between = if intervals.len() % 2 == 1 {
+        intervals[middle]
+    } else {
+        (intervals[middle - 1] + intervals[middle]) / 2.0
+    };
+    cadence.avg_days_between = intervals.iter().sum::<f64>() / intervals.len() as f64;
+    cadence.shortest_days_between = intervals[0];
+    cadence.longest_days_between = intervals[intervals.len() - 1];
+
+    cadence
+}
+
+fn calculate_commit_messages(
+    commits: &[git::CommitRecord],
+    issue_patterns: &[Regex],
+) -> CommitMessageStats {
+    let mut stats = CommitMessageStats {
+        commit_count: commits.len(),
+        ..CommitMessageStats::default()
+    };
+    let mut types: HashMap<String, usize> = HashMap::new();
+    let mut references: HashMap<String, usize> = HashMap::new();
+    let mut monthly: HashMap<String, MessageBucket> = HashMap::new();
+    let mut total_subject_length = 0;
+
+    for commit in commits {
+        let message = parse_commit_message(&commit.message);
+        let commit_references = find_issue_references(&commit.message, issue_patterns);
+
+        total_subject_length += message.subject_length;
+        if message.subject_length > MAX_SUBJECT_LENGTH {
+            stats.long_subjects += 1;
+        }
+        if message.has_body {
+            stats.with_body += 1;
+        }
+        if message.breaking {
+            stats.breaking_changes += 1;
+        }
+        if let Some(commit_type) = &message.commit_type {
+            stats.conventional_count += 1;
+            *types.entry(commit_type.clone()).or_insert(0) += 1;
+        }
+        if !commit_references.is_empty() {
+            stats.issue_linked_count += 1;
+        }
+        for reference in &commit_references {
+            *references.entry(reference.clone()).or_insert(0) += 1;
+        }
+
+        let period = commit.author_time.format("%Y-%m").to_string();
+        let bucket = monthly
+            .entry(period.clone())
+            .or_insert_with(|| MessageBucket {
+                period,
+                commits: 0,
+                features: 0,
+                fixes: 0,
+                issue_linked: 0,
+            });
+        bucket.commits += 1;
+        match message.commit_type.as_deref() {
+            Some("feat") => bucket.features += 1,
+            Some("fix") => bucket.fixes += 1,
+            _ => {}
+        }
+        if !commit_references.is_empty() {
+            bucket.issue_linked += 1;
+        }
+    }
+
+    if !commits.is_empty() {
+        stats.avg_subject_length = total_subject_length as f64 / commits.len() as f64;
+    }
+
+    stats.types = types.into_iter().collect();
+    stats
+        .types
+        .sort_by(|(a_type, a), (b_type, b)| b.cmp(a).then(a_type.cmp(b_type)));
+
+    stats.top_references = references.into_iter().collect();
+    stats
+        .top_references
+        .sort_by(|(a_ref, a), (b_ref, b)| b.cmp(a).then(a_ref.cmp(b_ref)));
+    stats.top_references.truncate(10);
+
+    stats.monthly = monthly.into_values().collect();
+    stats.monthly.sort_by(|a, b| a.period.cmp(&b.period));
+
+    stats
+}
+
+fn parse_commit_message(message: &str) -> ParsedMessage {
+    // Compiled once and shared by every commit of the walk
+    static CONVENTIONAL: OnceLock<Regex> = OnceLock::new();
+    static TRAILER: OnceLock<Regex> = OnceLock::new();
+    let conventional =
+        CONVENTIONAL.get_or_init(|| Regex::new(r"^(\w+)(?:\([^)]*\))?(!)?: \S").unwrap());
+    let trailer = TRAILER.get_or_init(|| Regex::new(r"^[A-Za-z][A-Za-z-]*: ").unwrap());
+
+    let mut lines = message.lines();
+    let subject = lines.next().unwrap_or("").trim();
+
+    let (commit_type, mut breaking) = match conventional.captures(subject) {
+        Some(captures) => {
+            let commit_type = captures[1].to_lowercase();
+            if CONVENTIONAL_TYPES.contains(&commit_type.as_str()) {
+                (Some(commit_type), captures.get(2).is_some())
+            } else {
+                (None, false)
+            }
+        }
+        None => (None, false),
+    };
+
+    let mut has_body = false;
+    for line in lines.map(str::trim).filter(|line| !line.is_empty()) {
+        if line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:") {
+            breaking = true;
+        } else if !trailer.is_match(line) {
+            has_body = true;
+        }
+    }
+
+    ParsedMessage {
+        subject_length: subject.chars().count(),
+        commit_type,
+        breaking,
+        has_body,
+    }
+}
+
+/// Issue references in a commit message, each reported once
+fn find_issue_references(message: &str, issue_patterns: &[Regex]) -> Vec<String> {
+    let mut references: Vec<String> = Vec::new();
+    for pattern in issue_patterns {
+        for found in pattern.find_iter(message) {
+            let reference = found.as_str().to_string();
+            if !references.contains(&reference) {
+                references.push(reference);
+            }
+        }
+    }
+    references
+}
+
+/// Issue reference patterns recognized without configuration: GitHub-style `#123`
+pub fn default_issue_patterns() -> Vec<Regex> {
+    vec![Regex::new(r"\B#\d+\b").unwrap()]
+}
+
+/// Counts how often each pair of paths changes in the same commit, keeping the
+/// 20 pairs with the most shared commits
+fn calculate_change_coupling(changesets: &[Vec<PathBuf>], commit_count: usize) -> Vec<CoupledPair> {
+    let mut changes: HashMap<&PathBuf, usize> = HashMap::new();
+    let mut co_changes: HashMap<(&PathBuf, &PathBuf), usize> = HashMap::new();
+
+    for changeset in changesets {
+        for path in changeset {
+            *changes.entry(path).or_insert(0) += 1;
+        }
+
+        if changeset.len() > MAX_CHANGESET_SIZE {
+            continue;
+        }
+
+        let mut paths: Vec<&PathBuf> = changeset.iter().collect();
+        paths.sort();
+        paths.dedup();
+        for (i, first) in paths.iter().enumerate() {
+            for second in &paths[i + 1..] {
+                *co_changes.entry((*first, *second)).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut pairs: Vec<CoupledPair> = co_changes
+        .into_iter()
+        .filter(|(_, count)| *count >= MIN_COUPLING_SUPPORT)
+        .map(|((first, second), count)| CoupledPair {
+            first: first.clone(),
+            second: second.clone(),
+            co_changes: count,
+            support: count as f64 / commit_count.max(1) as f64,
+            confidence_first_to_second: count as f64 / changes[first] as f64,
+            confidence_second_to_first: count as f64 / changes[second] as f64,
+        })
+        .collect();
+
+    pairs.sort_by(|a, b| {
+        let a_confidence = a
+            .confidence_first_to_second
+            .max(a.confidence_second_to_first);
+        let b_confidence = b
+            .confidence_first_to_second
+            .max(b.confidence_second_to_first);
+        b.co_changes
+            .cmp(&a.co_changes)
+            .then(
+                b_confidence
+                    .partial_cmp(&a_confidence)
+                    .unwrap_or(std::cmp::Ordering::Equal),
+            )
+            .then(a.first.cmp(&b.first))
+            .then(a.second.cmp(&b.second))
+    });
+    pairs.truncate(20);
+    pairs
 }
 
-fn analyze_git_history(
+fn analyze_ownership(
     repo_path: &Path,
     analysis: &mut RepositoryAnalysis,
-    history_depth: usize,
+    history_options: &git::HistoryOptions,
 ) -> Result<()> {
-    println!("Analyzing git history...");
+    println!("Analyzing code ownership...");
 
-    let (commit_count, contributors, last_activity, file_stats) =
-        git::analyze_git_repo_extended(repo_path, history_depth)
-            .context("Failed to analyze git repository")?;
+    let ownership = git::analyze_blame(repo_path, history_options, &classified_paths(analysis))
+        .context("Failed to compute code ownership")?;
+    analysis.ownership = Some(ownership);
 
-    analysis.commit_count = commit_count;
-    analysis.contributors = contributors;
-    analysis.last_activity = last_activity;
+    Ok(())
+}
 
-    // Process file age stats
-    let mut newest_files: Vec<(PathBuf, String)> = file_stats
+fn analyze_bus_factor(repo_path: &Path, analysis: &mut RepositoryAnalysis, inactive_months: u32) {
+    println!("Analyzing bus factor...");
+
+    // Most recent activity per author name, leaving bots out
+    let mut last_active: HashMap<&str, DateTime<FixedOffset>> = HashMap::new();
+    let mut bots: HashSet<&str> = HashSet::new();
+    for contributor in &analysis.contributors {
+        if contributor.is_bot {
+            bots.insert(&contributor.name);
+            continue;
+        }
+        let last = last_active
+            .entry(&contributor.name)
+            .or_insert(contributor.last_commit);
+        if contributor.last_commit > *last {
+            *last = contributor.last_commit;
+        }
+    }
+
+    let cutoff = Local::now().fixed_offset() - Duration::days(30 * inactive_months as i64);
+    let is_active = |author: &str| last_active.get(author).is_some_and(|last| *last >= cutoff);
+
+    // Knowledgeable authors per file, from blame ownership when available
+    // and from commit counts otherwise
+    let mut shares: HashMap<PathBuf, Vec<(String, f64)>> = HashMap::new();
+    if let Some(ownership) = &analysis.ownership {
+        for (path, owner) in &ownership.files {
+            let authors = owner
+                .authors_by_lines()
+                .into_iter()
+                .map(|(author, _, percentage)| (author.to_string(), percentage))
+                .collect();
+            shares.insert(path.clone(), authors);
+        }
+    } else {
+        for (path, stats) in &analysis.file_stats {
+            let mut authors: Vec<(String, f64)> = stats
+                .author_contributions
+                .iter()
+                .map(|(author, commits)| {
+                    let percentage = (*commits as f64 / stats.commit_count as f64) * 100.0;
+                    (author.clone(), percentage)
+                })
+                .collect();
+            authors.sort_by(|(a_name, a), (b_name, b)| {
+                b.partial_cmp(a)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then(a_name.cmp(b_name))
+            });
+            shares.insert(path.clone(), authors);
+        }
+    }
+
+    let mut knowledge: HashMap<PathBuf, Vec<String>> = HashMap::new();
+    for (path, authors) in shares {
+        let experts: Vec<String> = authors
+            .into_iter()
+            .filter(|(author, _)| !bots.contains(author.as_str()))
+            .enumerate()
+            .filter(|(i, (_, percentage))| *i == 0 || *percentage >= KNOWLEDGE_THRESHOLD)
+            .map(|(_, (author, _))| author)
+            .collect();
+        if !experts.is_empty() {
+            knowledge.insert(path, experts);
+        }
+    }
+
+    // Files whose knowledgeable authors have all gone inactive
+    let mut orphaned_files: Vec<OrphanedFile> = knowledge
         .iter()
-        .map(|(path, stats)| (path.clone(), stats.first_commit_date.clone()))
+        .filter(|(_, experts)| !experts.iter().any(|author| is_active(author)))
+        .map(|(path, experts)| OrphanedFile {
+            path: path.clone(),
+            owners: experts.clone(),
+            last_active: experts
+                .iter()
+                .filter_map(|author| last_active.get(author.as_str()))
+                .max()
+                .copied(),
+        })
         .collect();
-    newest_files.sort_by(|(_, a), (_, b)| b.cmp(a));
-    analysis.file_age_stats.newest_files = newest_files.into_iter().take(10).collect();
+    orphaned_files.sort_by(|a, b| a.last_active.cmp(&b.last_active).then(a.path.cmp(&b.path)));
 
-    let mut oldest_files: Vec<(PathBuf, String)> = file_stats
+    // Only active authors can still carry knowledge about a file
+    let active_knowledge: Vec<(&PathBuf, HashSet<&str>)> = knowledge
         .iter()
-        .map(|(path, stats)| (path.clone(), stats.first_commit_date.clone()))
+        .map(|(path, experts)| {
+            let active = experts
+                .iter()
+                .map(|author| author.as_str())
+                .filter(|author| is_active(author))
+                .collect();
+            (path, active)
+        })
         .collect();
-    oldest_files.sort_by(|(_, a), (_, b)| a.cmp(b));
-    analysis.file_age_stats.oldest_files = oldest_files.into_iter().take(10).collect();
 
-    let mut most_modified_files: Vec<(PathBuf, usize)> = file_stats
+    let mut top_level: Vec<PathBuf> = active_knowledge
         .iter()
-        .map(|(path, stats)| (path.clone(), stats.commit_count))
+        .filter_map(|(path, _)| {
+            let relative = path.strip_prefix(repo_path).ok()?;
+            let mut components = relative.components();
+            let first = components.next()?;
+            // Files directly in the repository root belong to no directory
+            components.next()?;
+            Some(repo_path.join(first))
+        })
+        .collect::<HashSet<PathBuf>>()
+        .into_iter()
         .collect();
-    most_modified_files.sort_by(|(_, a), (_, b)| b.cmp(a));
-    analysis.file_age_stats.most_modified_files =
-        most_modified_files.into_iter().take(10).collect();
+    top_level.sort();
+
+    let files: Vec<&HashSet<&str>> = active_knowledge.iter().map(|(_, a)| a).collect();
+    analysis.bus_factor = BusFactorStats {
+        inactive_months,
+        repository: calculate_bus_factor(&files),
+        directories: top_level
+            .into_iter()
+            .map(|dir| {
+                let files: Vec<&HashSet<&str>> = active_knowledge
+                    .iter()
+                    .filter(|(path, _)| path.starts_with(&dir))
+                    .map(|(_, a)| a)
+                    .collect();
+                (dir, calculate_bus_factor(&files))
+            })
+            .collect(),
+        orphaned_files,
+    };
+}
 
-    // Create most changed files info for the report
-    let mut most_changed_files = Vec::new();
-    for (path, stats) in file_stats.iter() {
-        // Find top contributor for this file
-        let mut top_contributor = String::from("Unknown");
-        let mut max_commits = 0;
+/// Greedily removes the author covering the most files until at most half of
+/// the files are left with a knowledgeable author
+fn calculate_bus_factor(files: &[&HashSet<&str>]) -> BusFactor {
+    let mut remaining: Vec<HashSet<&str>> =
+        files.iter().map(|authors| (*authors).clone()).collect();
+    let mut key_people = Vec::new();
+
+    loop {
+        let covered = remaining
+            .iter()
+            .filter(|authors| !authors.is_empty())
+            .count();
+        if covered * 2 <= remaining.len() {
+            break;
+        }
 
-        for (author, commit_count) in &stats.author_contributions {
-            if *commit_count > max_commits {
-                max_commits = *commit_count;
-                top_contributor = author.clone();
+        let mut coverage: HashMap<&str, usize> = HashMap::new();
+        for authors in &remaining {
+            for author in authors {
+                *coverage.entry(author).or_insert(0) += 1;
             }
         }
 
-        most_changed_files.push((
-            path.clone(),
-            stats.commit_count,
-            stats.lines_added,
-            stats.lines_removed,
-            stats.change_frequency,
-            top_contributor,
-            stats.last_commit_date.clone(),
-            stats.avg_changes_per_commit,
-        ));
-    }
-
-    // Sort by change frequency
-    most_changed_files.sort_by(|(_, _, _, _, a, _, _, _), (_, _, _, _, b, _, _, _)| {
-        b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal)
-    });
+        let top_author = coverage
+            .into_iter()
+            .max_by(|(a_name, a), (b_name, b)| a.cmp(b).then(b_name.cmp(a_name)))
+            .map(|(author, _)| author);
+        let Some(top_author) = top_author else { break };
 
-    // Store the top 10 most changed files
-    analysis.most_changed_files = most_changed_files.into_iter().take(10).collect();
+        for authors in remaining.iter_mut() {
+            authors.remove(top_author);
+        }
+        key_people.push(top_author.to_string());
+    }
 
-    Ok(())
+    BusFactor {
+        bus_factor: key_people.len(),
+        key_people,
+        file_count: files.len(),
+    }
 }
 
-fn analyze_code_complexity(repo_path: &Path, analysis: &mut RepositoryAnalysis) -> Result<()> {
+fn analyze_code_complexity(
+    repo_path: &Path,
+    analysis: &mut RepositoryAnalysis,
+    path_filter: &git::PathFilter,
+) -> Result<()> {
     println!("Analyzing code complexity...");
 
     let mut total_complexity = 0;
@@ -389,12 +1937,9 @@ fn analyze_code_complexity(repo_path: &Path, analysis: &mut RepositoryAnalysis)
         ("go", (Regex::new(r"func\s+(\w+)\s*\(").unwrap(), Regex::new(r"\{").unwrap(), Regex::new(r"\}").unwrap())),
     ]);
 
-    for entry in WalkDir::new(repo_path)
-        .into_iter()
-        .filter_entry(|e| !is_ignored(e.path(), &ignore_patterns()))
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-    {
+    let excluded_paths = analysis.excluded_paths.clone();
+    let classified = classified_paths(analysis);
+    for entry in source_files(repo_path, &excluded_paths, &classified, path_filter) {
         if let Some(ext) = entry.path().extension() {
             let ext_str = ext.to_str().unwrap_or("").to_lowercase();
 
@@ -405,6 +1950,10 @@ fn analyze_code_complexity(repo_path: &Path, analysis: &mut RepositoryAnalysis)
                     let complexity = calculate_cyclomatic_complexity(&content, &ext_str);
                     total_complexity += complexity;
                     file_count += 1;
+                    analysis
+                        .complexity_stats
+                        .file_complexity
+                        .insert(entry.path().to_path_buf(), complexity);
 
                     if complexity > 10 {
                         complex_files.push((entry.path().to_path_buf(), complexity));
@@ -459,6 +2008,525 @@ fn analyze_code_complexity(repo_path: &Path, analysis: &mut RepositoryAnalysis)
     Ok(())
 }
 
+fn analyze_hotspots(analysis: &mut RepositoryAnalysis) {
+    println!("Analyzing hotspots...");
+
+    let file_complexity = &analysis.complexity_stats.file_complexity;
+    let max_commits = analysis
+        .file_stats
+        .values()
+        .map(|stats| stats.commit_count)
+        .max()
+        .unwrap_or(0);
+    let max_complexity = file_complexity.values().copied().max().unwrap_or(0);
+
+    if max_commits == 0 || max_complexity == 0 {
+        return;
+    }
+
+    let mut hotspots: Vec<Hotspot> = analysis
+        .file_stats
+        .iter()
+        .filter_map(|(path, stats)| {
+            let complexity = *file_complexity.get(path)?;
+            let churn_score = stats.commit_count as f64 / max_commits as f64;
+            let complexity_score = complexity as f64 / max_complexity as f64;
+
+            Some(Hotspot {
+                path: path.clone(),
+                commit_count: stats.commit_count,
+                lines_changed: stats.lines_added + stats.lines_removed,
+                change_frequency: stats.change_frequency,
+                complexity,
+                score: churn_score * complexity_score,
+            })
+        })
+        .collect();
+
+    hotspots.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(b.lines_changed.cmp(&a.lines_changed))
+            .then(a.path.cmp(&b.path))
+    });
+    analysis.hotspots = hotspots.into_iter().take(10).collect();
+}
+
+/// Find the components of a repository. Metrics are filled in later by
+/// `analyze_component_history` and `summarize_components`
+fn detect_components(repo_path: &Path, mode: ComponentMode) -> Vec<Component> {
+    let mut components = match mode {
+        ComponentMode::Directories => top_level_directories(repo_path),
+        ComponentMode::Packages => {
+            let packages = detect_packages(repo_path);
+            if packages.is_empty() {
+                println!("No packages detected, breaking down by top-level directory");
+                top_level_directories(repo_path)
+            } else {
+                packages
+            }
+        }
+    };
+
+    // Everything outside the other components
+    components.push(new_component(
+        "(root)".to_string(),
+        repo_path.to_path_buf(),
+        ComponentKind::Root,
+    ));
+    components
+}
+
+fn new_component(name: String, path: PathBuf, kind: ComponentKind) -> Component {
+    Component {
+        name,
+        path,
+        kind,
+        file_count: 0,
+        total_lines: 0,
+        language_lines: HashMap::new(),
+        avg_complexity: 0.0,
+        max_complexity: 0,
+        commit_count: 0,
+        lines_added: 0,
+        lines_removed: 0,
+        contributors: Vec::new(),
+        complex_files: Vec::new(),
+        changed_files: Vec::new(),
+    }
+}
+
+fn top_level_directories(repo_path: &Path) -> Vec<Component> {
+    let mut directories: Vec<PathBuf> = std::fs::read_dir(repo_path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .filter(|path| {
+                    let relative = path.strip_prefix(repo_path).unwrap_or(path);
+                    !is_ignored(relative, true)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    directories.sort();
+
+    directories
+        .into_iter()
+        .map(|path| {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            new_component(name, path, ComponentKind::Directory)
+        })
+        .collect()
+}
+
+/// Packages declared by Cargo workspaces, npm and pnpm workspaces, Go modules
+/// and Maven modules. A directory claimed by several build systems is listed once
+fn detect_packages(repo_path: &Path) -> Vec<Component> {
+    let mut packages: Vec<Component> = Vec::new();
+    let mut add = |package: Component| {
+        if !packages
+            .iter()
+            .any(|existing| existing.path == package.path)
+        {
+            packages.push(package);
+        }
+    };
+
+    for path in cargo_workspace_members(repo_path) {
+        let name = read_manifest_value(
+            &path.join("Cargo.toml"),
+            r#"(?s)\[package\].*?\bname\s*=\s*"([^"]+)""#,
+        );
+        add(package_component(
+            name,
+            path,
+            repo_path,
+            ComponentKind::Cargo,
+        ));
+    }
+
+    let (kind, patterns) = if repo_path.join("pnpm-workspace.yaml").exists() {
+        (ComponentKind::Pnpm, pnpm_workspace_patterns(repo_path))
+    } else {
+        (ComponentKind::Npm, npm_workspace_patterns(repo_path))
+    };
+    for path in expand_workspace_patterns(repo_path, &patterns) {
+        if !path.join("package.json").is_file() {
+            continue;
+        }
+        let name = std::fs::read_to_string(path.join("package.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|manifest| manifest["name"].as_str().map(|name| name.to_string()));
+        add(package_component(name, path, repo_path, kind));
+    }
+
+    for path in go_modules(repo_path) {
+        let name = read_manifest_value(&path.join("go.mod"), r"(?m)^module\s+(\S+)");
+        add(package_component(name, path, repo_path, ComponentKind::Go));
+    }
+
+    // The artifact id of the module itself, not of its parent
+    let parent = Regex::new(r"(?s)<parent>.*?</parent>").unwrap();
+    let artifact_id = Regex::new(r"<artifactId>\s*([^<\s]+)\s*</artifactId>").unwrap();
+    for path in maven_modules(repo_path) {
+        let name = std::fs::read_to_string(path.join("pom.xml"))
+            .ok()
+            .and_then(|content| {
+                artifact_id
+                    .captures(&parent.replace(&content, ""))
+                    .map(|captures| captures[1].to_string())
+            });
+        add(package_component(
+            name,
+            path,
+            repo_path,
+            ComponentKind::Maven,
+        ));
+    }
+
+    packages
+}
+
+/// A package named after its manifest, or after its directory when unnamed
+fn package_component(
+    name: Option<String>,
+    path: PathBuf,
+    repo_path: &Path,
+    kind: ComponentKind,
+) -> Component {
+    let name = name.unwrap_or_else(|| {
+        path.strip_prefix(repo_path)
+            .unwrap_or(&path)
+            .display()
+            .to_string()
+    });
+    new_component(name, path, kind)
+}
+
+/// The first capture of a pattern in a manifest file
+fn read_manifest_value(path: &Path, pattern: &str) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    Regex::new(pattern)
+        .unwrap()
+        .captures(&content)
+        .map(|captures| captures[1].to_string())
+}
+
+/// Quoted strings inside a TOML or JSON style array
+fn quoted_strings(list: &str) -> Vec<String> {
+    Regex::new(r#""([^"]*)"|'([^']*)'"#)
+        .unwrap()
+        .captures_iter(list)
+        .filter_map(|captures| captures.get(1).or_else(|| captures.get(2)))
+        .map(|value| value.as_str().to_string())
+        .collect()
+}
+
+fn cargo_workspace_members(repo_path: &Path) -> Vec<PathBuf> {
+    let Ok(content) = std::fs::read_to_string(repo_path.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let pattern = Regex::new(r"(?s)\[workspace\][^\[]*?members\s*=\s*\[(.*?)\]").unwrap();
+    let patterns = pattern
+        .captures(&content)
+        .map(|captures| quoted_strings(&captures[1]))
+        .unwrap_or_default();
+    expand_workspace_patterns(repo_path, &patterns)
+        .into_iter()
+        .filter(|path| path.join("Cargo.toml").is_file())
+        .collect()
+}
+
+fn npm_workspace_patterns(repo_path: &Path) -> Vec<String> {
+    let manifest = std::fs::read_to_string(repo_path.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+    let Some(manifest) = manifest else {
+        return Vec::new();
+    };
+    // Either an array of patterns or an object with a "packages" array
+    let workspaces = match &manifest["workspaces"] {
+        serde_json::Value::Object(object) => object.get("packages").cloned(),
+        other => Some(other.clone()),
+    };
+    workspaces
+        .and_then(|workspaces| workspaces.as_array().cloned())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|pattern| pattern.as_str().map(|pattern| pattern.to_string()))
+        .collect()
+}
+
+fn pnpm_workspace_patterns(repo_path: &Path) -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(repo_path.join("pnpm-workspace.yaml")) else {
+        return Vec::new();
+    };
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if !line.starts_with(' ') && !line.starts_with('-') && !trimmed.is_empty() {
+            in_packages = trimmed.starts_with("packages:");
+        } else if in_packages {
+            if let Some(pattern) = trimmed.strip_prefix('-') {
+                patterns.push(pattern.trim().trim_matches(['"', '\'']).to_string());
+            }
+        }
+    }
+    patterns
+}
+
+/// Go modules listed by go.work, or every directory holding a go.mod
+fn go_modules(repo_path: &Path) -> Vec<PathBuf> {
+    if let Ok(content) = std::fs::read_to_string(repo_path.join("go.work")) {
+        let block = Regex::new(r"(?s)\buse\s*\((.*?)\)").unwrap();
+        let single = Regex::new(r"(?m)^use\s+([^\s(]+)").unwrap();
+        let mut modules: Vec<PathBuf> = block
+            .captures_iter(&content)
+            .flat_map(|captures| {
+                captures[1]
+                    .split_whitespace()
+                    .map(|path| path.trim_matches('"').to_string())
+                    .collect::<Vec<_>>()
+            })
+            .chain(
+                single
+                    .captures_iter(&content)
+                    .map(|captures| captures[1].trim_matches('"').to_string()),
+            )
+            .map(|path| normalize_path(&repo_path.join(path)))
+            .filter(|path| path.join("go.mod").is_file())
+            .collect();
+        modules.sort();
+        modules.dedup();
+        return modules;
+    }
+
+    let mut modules: Vec<PathBuf> = WalkDir::new(repo_path)
+        .into_iter()
+        .filter_entry(|e| {
+            let relative = e.path().strip_prefix(repo_path).unwrap_or(e.path());
+            !is_ignored(relative, e.file_type().is_dir()) && e.file_name() != "vendor"
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.file_name() == "go.mod")
+        .filter_map(|e| e.path().parent().map(|dir| dir.to_path_buf()))
+        .collect();
+    modules.sort();
+    modules
+}
+
+/// Maven modules declared by the root pom.xml and, recursively, by their own poms
+fn maven_modules(repo_path: &Path) -> Vec<PathBuf> {
+    let module = Regex::new(r"<module>\s*([^<\s]+)\s*</module>").unwrap();
+    let mut modules = Vec::new();
+    let mut pending = vec![repo_path.to_path_buf()];
+    while let Some(parent) = pending.pop() {
+        let Ok(content) = std::fs::read_to_string(parent.join("pom.xml")) else {
+            continue;
+        };
+        for captures in module.captures_iter(&content) {
+            let path = normalize_path(&parent.join(&captures[1]));
+            if path.join("pom.xml").is_file() && !modules.contains(&path) {
+                modules.push(path.clone());
+                pending.push(path);
+            }
+        }
+    }
+    modules.sort();
+    modules
+}
+
+/// Directories matching workspace patterns such as "crates/*" or "packages/**".
+/// Patterns starting with '!' exclude directories matched by earlier ones
+fn expand_workspace_patterns(repo_path: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    let mut directories: Vec<PathBuf> = Vec::new();
+    for pattern in patterns {
+        let (negated, pattern) = match pattern.strip_prefix('!') {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern.as_str()),
+        };
+        let mut matches = vec![repo_path.to_path_buf()];
+        for segment in pattern
+            .trim_start_matches("./")
+            .trim_end_matches('/')
+            .split('/')
+        {
+            matches = matches
+                .iter()
+                .flat_map(|dir| expand_segment(dir, segment))
+                .collect();
+        }
+        for path in matches {
+            let path = normalize_path(&path);
+            if negated {
+                directories.retain(|existing| *existing != path);
+            } else if path.is_dir() && !directories.contains(&path) {
+                directories.push(path);
+            }
+        }
+    }
+    directories.sort();
+    directories
+}
+
+fn expand_segment(dir: &Path, segment: &str) -> Vec<PathBuf> {
+    if segment == "**" {
+        return WalkDir::new(dir)
+            .into_iter()
+            .filter_entry(|e| {
+                let relative = e.path().strip_prefix(dir).unwrap_or(e.path());
+                !is_ignored(relative, e.file_type().is_dir()) && e.file_name() != "node_modules"
+            })
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_dir())
+            .map(|e| e.path().to_path_buf())
+            .collect();
+    }
+    if !segment.contains(['*', '?']) {
+        return vec![dir.join(segment)];
+    }
+
+    let pattern = format!(
+        "^{}$",
+        regex::escape(segment)
+            .replace(r"\*", ".*")
+            .replace(r"\?", ".")
+    );
+    let Ok(pattern) = Regex::new(&pattern) else {
+        return Vec::new();
+    };
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter(|entry| pattern.is_match(&entry.file_name().to_string_lossy()))
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Resolve "." and ".." components without touching the file system
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// The component a file belongs to: the one with the deepest matching root
+fn component_of(components: &[Component], path: &Path) -> Option<usize> {
+    components
+        .iter()
+        .enumerate()
+        .filter(|(_, component)| path.starts_with(&component.path))
+        .max_by_key(|(_, component)| component.path.components().count())
+        .map(|(index, _)| index)
+}
+
+/// Count the commits and contributors of each component. Only files that
+/// count towards churn are considered, so vendored, generated, excluded and
+/// deleted files do not credit a component with a commit
+fn analyze_component_history(
+    components: &mut [Component],
+    commits: &[git::CommitRecord],
+    file_stats: &HashMap<PathBuf, git::FileStats>,
+) {
+    let mut contributors: Vec<HashMap<String, usize>> = vec![HashMap::new(); components.len()];
+    for commit in commits {
+        let touched: HashSet<usize> = commit
+            .files
+            .iter()
+            .filter(|path| file_stats.contains_key(*path))
+            .filter_map(|path| component_of(components, path))
+            .collect();
+        for index in touched {
+            components[index].commit_count += 1;
+            *contributors[index]
+                .entry(commit.author.clone())
+                .or_insert(0) += 1;
+        }
+    }
+
+    for (component, authors) in components.iter_mut().zip(contributors) {
+        let mut authors: Vec<(String, usize)> = authors.into_iter().collect();
+        authors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        component.contributors = authors;
+    }
+}
+
+/// Aggregate file, line, complexity and churn metrics of each component, then
+/// drop components without files or commits and sort the largest first
+fn summarize_components(analysis: &mut RepositoryAnalysis) {
+    println!("Analyzing components...");
+
+    let mut components = std::mem::take(&mut analysis.components);
+    let mut complexities: Vec<Vec<(PathBuf, usize)>> = vec![Vec::new(); components.len()];
+    let mut changes: Vec<Vec<(PathBuf, usize)>> = vec![Vec::new(); components.len()];
+
+    for (path, lines) in &analysis.file_lines {
+        let Some(index) = component_of(&components, path) else {
+            continue;
+        };
+        let component = &mut components[index];
+        component.file_count += 1;
+        component.total_lines += lines;
+        if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+            let language = language_for_extension(&ext.to_lowercase());
+            *component
+                .language_lines
+                .entry(language.to_string())
+                .or_insert(0) += lines;
+        }
+        if let Some(complexity) = analysis.complexity_stats.file_complexity.get(path) {
+            complexities[index].push((path.clone(), *complexity));
+        }
+    }
+
+    for (path, stats) in &analysis.file_stats {
+        if let Some(index) = component_of(&components, path) {
+            components[index].lines_added += stats.lines_added;
+            components[index].lines_removed += stats.lines_removed;
+            changes[index].push((path.clone(), stats.commit_count));
+        }
+    }
+
+    for (index, component) in components.iter_mut().enumerate() {
+        let mut files = std::mem::take(&mut complexities[index]);
+        if !files.is_empty() {
+            let total: usize = files.iter().map(|(_, complexity)| complexity).sum();
+            component.avg_complexity = total as f64 / files.len() as f64;
+            component.max_complexity = files.iter().map(|(_, c)| *c).max().unwrap_or(0);
+        }
+        files.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        component.complex_files = files.into_iter().take(5).collect();
+
+        let mut files = std::mem::take(&mut changes[index]);
+        files.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        component.changed_files = files.into_iter().take(5).collect();
+    }
+
+    components.retain(|component| component.file_count > 0 || component.commit_count > 0);
+    components.sort_by(|a, b| b.total_lines.cmp(&a.total_lines).then(a.name.cmp(&b.name)));
+    analysis.components = components;
+}
+
 fn calculate_cyclomatic_complexity(content: &str, ext: &str) -> usize {
     // Base complexity is 1
     let mut complexity = 1;
@@ -668,19 +2736,20 @@ fn find_functions(
     functions
 }
 
-fn find_duplicate_code(repo_path: &Path, analysis: &mut RepositoryAnalysis) -> Result<()> {
+fn find_duplicate_code(
+    repo_path: &Path,
+    analysis: &mut RepositoryAnalysis,
+    path_filter: &git::PathFilter,
+) -> Result<()> {
     println!("Finding duplicate code...");
 
     // Simple duplicate code detection using line hashing
     let mut file_contents: HashMap<PathBuf, Vec<String>> = HashMap::new();
 
     // Read file contents
-    for entry in WalkDir::new(repo_path)
-        .into_iter()
-        .filter_entry(|e| !is_ignored(e.path(), &ignore_patterns()))
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-    {
+    let excluded_paths = analysis.excluded_paths.clone();
+    let classified = classified_paths(analysis);
+    for entry in source_files(repo_path, &excluded_paths, &classified, path_filter) {
         if let Some(ext) = entry.path().extension() {
             let ext_str = ext.to_str().unwrap_or("").to_lowercase();
 
@@ -744,12 +2813,8 @@ fn find_duplicate_code(repo_path: &Path, analysis: &mut RepositoryAnalysis) -> R
 
             for (_, _, size) in duplicate_blocks {
                 if size >= min_block_size {
-                    let mut files_vec = Vec::new();
-                    files_vec.push(file1.clone());
-                    files_vec.push(file2.clone());
-
                     duplicates.push(DuplicateCode {
-                        files: files_vec,
+                        files: vec![file1.clone(), file2.clone()],
                         line_count: size,
                         similarity: 1.0, // Perfect match
                     });
@@ -759,27 +2824,294 @@ fn find_duplicate_code(repo_path: &Path, analysis: &mut RepositoryAnalysis) -> R
     }
 
     // Sort by line count and take top 10
-    duplicates.sort_by(|a, b| b.line_count.cmp(&a.line_count));
+    duplicates.sort_by_key(|d| std::cmp::Reverse(d.line_count));
     analysis.duplicate_code = duplicates.into_iter().take(10).collect();
 
     Ok(())
 }
 
-fn ignore_patterns() -> Vec<Regex> {
-    vec![
-        Regex::new(r"\.git/").unwrap(),
-        Regex::new(r"node_modules/").unwrap(),
-        Regex::new(r"target/").unwrap(),
-        Regex::new(r"\.DS_Store").unwrap(),
-        Regex::new(r"\.idea/").unwrap(),
-        Regex::new(r"\.vscode/").unwrap(),
-        Regex::new(r"dist/").unwrap(),
-        Regex::new(r"build/").unwrap(),
-        Regex::new(r"\.cache/").unwrap(),
-    ]
+/// Directories never analyzed, wherever they are in the repository
+const IGNORED_DIRECTORIES: &[&str] = &[
+    ".git",
+    "node_modules",
+    "target",
+    ".idea",
+    ".vscode",
+    "dist",
+    "build",
+    ".cache",
+];
+
+/// Files never analyzed, wherever they are in the repository
+const IGNORED_FILES: &[&str] = &[".DS_Store"];
+
+/// Files of the working tree the analyses look at: outside the default ignore
+/// list, excluded directories and skipped files, and within the include and
+/// exclude globs
+fn source_files<'a>(
+    repo_path: &'a Path,
+    excluded_paths: &'a [PathBuf],
+    skipped_files: &'a HashSet<PathBuf>,
+    path_filter: &'a git::PathFilter,
+) -> impl Iterator<Item = walkdir::DirEntry> + 'a {
+    WalkDir::new(repo_path)
+        .into_iter()
+        .filter_entry(move |e| {
+            let relative = e.path().strip_prefix(repo_path).unwrap_or(e.path());
+            let in_scope = if e.file_type().is_dir() {
+                !path_filter.excludes_dir(relative)
+            } else {
+                path_filter.matches(relative)
+            };
+            in_scope
+                && !is_ignored(relative, e.file_type().is_dir())
+                && !is_excluded(e.path(), excluded_paths)
+                && !skipped_files.contains(e.path())
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+}
+
+/// Paths of the vendored, generated and minified files
+fn classified_paths(analysis: &RepositoryAnalysis) -> HashSet<PathBuf> {
+    analysis
+        .classified_files
+        .iter()
+        .map(|file| file.path.clone())
+        .collect()
+}
+
+/// Whether a path lies inside one of the excluded directories
+fn is_excluded(path: &Path, excluded_paths: &[PathBuf]) -> bool {
+    excluded_paths
+        .iter()
+        .any(|excluded| path.starts_with(excluded))
+}
+
+/// Whether a path relative to the repository root lies in an ignored
+/// directory or is an ignored file. Whole path components are compared, so
+/// where the repository itself is checked out does not matter
+fn is_ignored(relative: &Path, is_dir: bool) -> bool {
+    let names: Vec<&std::ffi::OsStr> = relative.iter().collect();
+    let (file_name, directories) = match names.split_last() {
+        Some((last, parents)) if !is_dir => (Some(*last), parents),
+        _ => (None, &names[..]),
+    };
+    directories
+        .iter()
+        .any(|name| IGNORED_DIRECTORIES.iter().any(|ignored| name == ignored))
+        || file_name.is_some_and(|name| IGNORED_FILES.iter().any(|ignored| name == *ignored))
 }
 
-fn is_ignored(path: &Path, patterns: &[Regex]) -> bool {
-    let path_str = path.to_string_lossy();
-    patterns.iter().any(|pattern| pattern.is_match(&path_str))
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignored_directories_match_whole_components() {
+        assert!(is_ignored(Path::new("target/debug/app"), false));
+        assert!(is_ignored(Path::new("web/node_modules"), true));
+        assert!(is_ignored(Path::new("docs/.DS_Store"), false));
+        assert!(!is_ignored(Path::new("src/build.rs"), false));
+        assert!(!is_ignored(Path::new("targets/main.rs"), false));
+        assert!(!is_ignored(Path::new("src/rebuild/main.rs"), false));
+        // A file named like an ignored directory is still analyzed
+        assert!(!is_ignored(Path::new("scripts/build"), false));
+    }
+
+    fn bus_factor(files: &[&[&'static str]]) -> BusFactor {
+        let files: Vec<HashSet<&str>> = files
+            .iter()
+            .map(|authors| authors.iter().copied().collect())
+            .collect();
+        calculate_bus_factor(&files.iter().collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn bus_factor_removes_the_widest_author_first() {
+        let result = bus_factor(&[&["alice"], &["alice"], &["alice", "bob"], &["carol"]]);
+
+        assert_eq!(result.bus_factor, 1);
+        assert_eq!(result.key_people, vec!["alice"]);
+        assert_eq!(result.file_count, 4);
+    }
+
+    #[test]
+    fn bus_factor_stops_once_half_the_files_are_orphaned() {
+        // Ties go to the name sorting first, so the result is stable
+        let result = bus_factor(&[&["dave"], &["carol"], &["bob"], &["alice"]]);
+
+        assert_eq!(result.bus_factor, 2);
+        assert_eq!(result.key_people, vec!["alice", "bob"]);
+    }
+
+    #[test]
+    fn bus_factor_is_zero_without_knowledgeable_authors() {
+        assert_eq!(bus_factor(&[]).bus_factor, 0);
+        assert_eq!(bus_factor(&[&[], &[], &["alice"]]).bus_factor, 0);
+    }
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let values = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+        assert_eq!(percentile(&values, 50.0), 5);
+        assert_eq!(percentile(&values, 90.0), 9);
+        assert_eq!(percentile(&values, 91.0), 10);
+        assert_eq!(percentile(&values, 0.0), 1);
+        assert_eq!(percentile(&values, 100.0), 10);
+        assert_eq!(percentile(&[42], 90.0), 42);
+        assert_eq!(percentile(&[], 50.0), 0);
+    }
+
+    #[test]
+    fn conventional_subjects_give_the_type_and_scope() {
+        let parsed = parse_commit_message("Feat(parser): support tables");
+
+        assert_eq!(parsed.commit_type.as_deref(), Some("feat"));
+        assert!(!parsed.breaking);
+        assert!(!parsed.has_body);
+        assert_eq!(parsed.subject_length, 28);
+
+        assert_eq!(parse_commit_message("Update README").commit_type, None);
+        assert_eq!(parse_commit_message("wip: try things").commit_type, None);
+        assert_eq!(parse_commit_message("fix:no space").commit_type, None);
+    }
+
+    #[test]
+    fn breaking_changes_are_marked_in_the_subject_or_a_footer() {
+        assert!(parse_commit_message("refactor(api)!: drop v1 endpoints").breaking);
+
+        let parsed = parse_commit_message(
+            "feat: new config format\n\nBREAKING CHANGE: the old format is rejected\n",
+        );
+        assert_eq!(parsed.commit_type.as_deref(), Some("feat"));
+        assert!(parsed.breaking);
+        // The footer is a trailer rather than a body
+        assert!(!parsed.has_body);
+
+        assert!(parse_commit_message("fix: x\n\nBREAKING-CHANGE: y").breaking);
+        assert!(!parse_commit_message("fix: x\n\nNot a BREAKING CHANGE: y").breaking);
+    }
+
+    #[test]
+    fn trailers_alone_are_not_a_body() {
+        let parsed = parse_commit_message(
+            "fix: typo\n\nSigned-off-by: Alice <alice@example.com>\nRefs: #12\n",
+        );
+
+        assert!(!parsed.has_body);
+        assert!(parse_commit_message("fix: typo\n\nThe word was misspelled.").has_body);
+    }
+
+    #[test]
+    fn issue_references_are_reported_once() {
+        let patterns = default_issue_patterns();
+
+        assert_eq!(
+            find_issue_references("Fix #12 and #7, see #12 and a#3", &patterns),
+            vec!["#12", "#7"]
+        );
+        assert!(find_issue_references("Color #fff", &patterns).is_empty());
+    }
+
+    fn classify(
+        path: &str,
+        content: Option<&str>,
+        attributes: &git::LinguistAttributes,
+    ) -> Option<FileClass> {
+        classify_file(
+            Path::new(path),
+            content,
+            attributes,
+            &generated_path_patterns(),
+        )
+        .map(|(class, _)| class)
+    }
+
+    #[test]
+    fn files_are_classified_by_path_and_content() {
+        // Outside a git repository no attributes apply
+        let attributes = git::LinguistAttributes::open(Path::new("/nonexistent"));
+        let minified = format!("var a={};\n", "1".repeat(200));
+
+        assert_eq!(
+            classify("vendor/lib/util.go", None, &attributes),
+            Some(FileClass::Vendored)
+        );
+        assert_eq!(
+            classify("web/Cargo.lock", None, &attributes),
+            Some(FileClass::Generated)
+        );
+        assert_eq!(
+            classify("api/user.pb.go", None, &attributes),
+            Some(FileClass::Generated)
+        );
+        assert_eq!(
+            classify("static/app.min.js", None, &attributes),
+            Some(FileClass::Minified)
+        );
+        assert_eq!(
+            classify(
+                "src/schema.rs",
+                Some("// @generated by schema-gen\nfn a() {}\n"),
+                &attributes
+            ),
+            Some(FileClass::Generated)
+        );
+        assert_eq!(
+            classify("static/app.js", Some(&minified), &attributes),
+            Some(FileClass::Minified)
+        );
+        assert_eq!(
+            classify("src/vendoring.rs", Some("fn main() {}\n"), &attributes),
+            None
+        );
+    }
+
+    /// A scratch directory, removed when dropped
+    struct ScratchDir(PathBuf);
+
+    impl Drop for ScratchDir {
+        fn drop(&mut self) {
+            std::fs::remove_dir_all(&self.0).unwrap_or(());
+        }
+    }
+
+    #[test]
+    fn linguist_attributes_override_the_heuristics() {
+        let scratch = ScratchDir(std::env::temp_dir().join(format!(
+            "repo-analyzer-unit-{}-linguist",
+            std::process::id()
+        )));
+        std::fs::remove_dir_all(&scratch.0).unwrap_or(());
+        git2::Repository::init(&scratch.0).unwrap();
+        std::fs::write(
+            scratch.0.join(".gitattributes"),
+            "Cargo.lock linguist-generated=false\n\
+             *.pb.go linguist-generated=false\n\
+             vendor/** -linguist-vendored\n\
+             src/schema.rs linguist-generated\n\
+             lib/** linguist-vendored\n",
+        )
+        .unwrap();
+        let attributes = git::LinguistAttributes::open(&scratch.0);
+
+        assert_eq!(classify("Cargo.lock", None, &attributes), None);
+        assert_eq!(classify("api/user.pb.go", None, &attributes), None);
+        assert_eq!(classify("vendor/util.go", None, &attributes), None);
+        // Unsetting one attribute leaves the other heuristics in place
+        assert_eq!(
+            classify("vendor/yarn.lock", None, &attributes),
+            Some(FileClass::Generated)
+        );
+        assert_eq!(
+            classify("src/schema.rs", None, &attributes),
+            Some(FileClass::Generated)
+        );
+        assert_eq!(
+            classify("lib/util.go", None, &attributes),
+            Some(FileClass::Vendored)
+        );
+    }
 }
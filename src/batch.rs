@@ -0,0 +1,259 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::analyzer::{self, AnalysisOptions, RepositoryAnalysis};
+use crate::git::{self, CloneOptions};
+use crate::report::{self, DisplayTimeZone};
+
+/// How a batch of repositories is analyzed and reported
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    pub jobs: usize,         // Number of repositories analyzed at the same time
+    pub output_dir: PathBuf, // Per-repository reports and the organization report go here
+    pub format: String,      // Report format (text, json, html)
+    pub top_contributors: usize,
+    pub time_zone: DisplayTimeZone,
+    pub clone: CloneOptions,
+    pub analysis: AnalysisOptions,
+}
+
+/// Organization-wide results of a batch analysis
+#[derive(Debug)]
+pub struct OrgAnalysis {
+    pub repositories: Vec<RepositorySummary>, // In manifest order
+    pub failures: Vec<(String, String)>,      // Source and error of each failed repository
+    pub language_lines: HashMap<String, usize>,
+    pub contributors: Vec<OrgContributor>, // Most widely active first
+    pub total_lines: usize,
+    pub code_lines: usize,
+    pub commit_count: usize,
+}
+
+/// The headline numbers of one analyzed repository
+#[derive(Debug)]
+pub struct RepositorySummary {
+    pub source: String, // Path or URL as listed in the manifest
+    pub report_path: PathBuf,
+    pub file_count: usize,
+    pub total_lines: usize,
+    pub code_lines: usize,
+    pub commit_count: usize,
+    pub contributor_count: usize,
+    pub avg_complexity: f64,
+    pub total_complexity: usize, // Sum of the complexity of every file
+    pub last_activity: Option<DateTime<FixedOffset>>,
+    pub language_lines: HashMap<String, usize>,
+    pub contributors: Vec<(String, String, usize)>, // Name, email and commit count
+}
+
+/// A contributor matched across repositories by email address
+#[derive(Debug, Clone)]
+pub struct OrgContributor {
+    pub name: String,
+    pub email: String,
+    pub repositories: usize,
+    pub commit_count: usize,
+}
+
+/// Read repository paths and URLs from a manifest file, or from stdin when the
+/// source is "-". Blank lines, lines starting with '#' and duplicates are skipped
+pub fn read_manifest(source: &str) -> Result<Vec<String>> {
+    let reader: Box<dyn BufRead> = if source == "-" {
+        Box::new(BufReader::new(io::stdin()))
+    } else {
+        let file =
+            File::open(source).with_context(|| format!("Failed to open manifest: {}", source))?;
+        Box::new(BufReader::new(file))
+    };
+
+    let mut entries: Vec<String> = Vec::new();
+    for line in reader.lines() {
+        let line = line.context("Failed to read manifest")?;
+        let entry = line.trim();
+        if entry.is_empty() || entry.starts_with('#') {
+            continue;
+        }
+        if !entries.iter().any(|existing| existing == entry) {
+            entries.push(entry.to_string());
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Analyze every repository with at most `options.jobs` running at once, write
+/// one report per repository and aggregate the results. A repository that fails
+/// to clone or analyze is recorded and does not stop the batch
+pub fn analyze_batch(sources: &[String], options: &BatchOptions) -> Result<OrgAnalysis> {
+    std::fs::create_dir_all(&options.output_dir).with_context(|| {
+        format!(
+            "Failed to create output directory: {}",
+            options.output_dir.display()
+        )
+    })?;
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<RepositorySummary>>>> =
+        Mutex::new((0..sources.len()).map(|_| None).collect());
+
+    let workers = options.jobs.clamp(1, sources.len().max(1));
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                if index >= sources.len() {
+                    break;
+                }
+                let source = &sources[index];
+                println!("Analyzing {} ({}/{})...", source, index + 1, sources.len());
+                let result = analyze_source(source, options);
+                match &result {
+                    Ok(_) => println!("Finished {}", source),
+                    Err(e) => println!("Failed to analyze {}: {:#}", source, e),
+                }
+                results.lock().unwrap()[index] = Some(result);
+            });
+        }
+    });
+
+    let mut repositories = Vec::new();
+    let mut failures = Vec::new();
+    for (source, result) in sources.iter().zip(results.into_inner().unwrap()) {
+        match result {
+            Some(Ok(summary)) => repositories.push(summary),
+            Some(Err(e)) => failures.push((source.clone(), format!("{:#}", e))),
+            None => failures.push((source.clone(), "Not analyzed".to_string())),
+        }
+    }
+
+    Ok(aggregate(repositories, failures))
+}
+
+/// Clone the source if it is remote, analyze it and write its report
+fn analyze_source(source: &str, options: &BatchOptions) -> Result<RepositorySummary> {
+    // A temporary clone is removed once `_clone` goes out of scope
+    let mut _clone = None;
+    let repo_path = if is_remote(source) {
+        let clone = git::clone_remote(source, &options.clone)?;
+        let path = clone.path.clone();
+        _clone = Some(clone);
+        path
+    } else {
+        PathBuf::from(source)
+    };
+    if !repo_path.is_dir() {
+        return Err(anyhow::anyhow!(
+            "Repository not found: {}",
+            repo_path.display()
+        ));
+    }
+
+    let analysis = analyzer::analyze_repository(&repo_path, &options.analysis)?;
+
+    // Text reports would interleave on the console, so each repository gets a
+    // JSON report instead and only the organization report is printed
+    let extension = match options.format.to_lowercase().as_str() {
+        "html" => "html",
+        _ => "json",
+    };
+    // The cache key carries a hash of the whole source, so sources that only
+    // differ in punctuation do not overwrite each other's report
+    let report_path = options
+        .output_dir
+        .join(format!("{}.{}", git::cache_key(source)?, extension));
+    let report_path = report::generate_report_file(
+        &analysis,
+        extension,
+        options.top_contributors,
+        options.time_zone,
+        report_path,
+    )?;
+
+    Ok(summarize(source, &analysis, report_path))
+}
+
+/// Whether a manifest entry is a URL rather than a local path, including
+/// scp-like SSH addresses such as git@github.com:org/repo.git
+fn is_remote(source: &str) -> bool {
+    source.contains("://")
+        || (source.contains('@') && source.contains(':') && !Path::new(source).exists())
+}
+
+fn summarize(
+    source: &str,
+    analysis: &RepositoryAnalysis,
+    report_path: PathBuf,
+) -> RepositorySummary {
+    RepositorySummary {
+        source: source.to_string(),
+        report_path,
+        file_count: analysis.file_count,
+        total_lines: analysis.total_lines,
+        code_lines: analysis.code_lines,
+        commit_count: analysis.commit_count,
+        contributor_count: analysis.contributors.iter().filter(|c| !c.is_bot).count(),
+        avg_complexity: analysis.complexity_stats.avg_complexity,
+        total_complexity: analysis.complexity_stats.file_complexity.values().sum(),
+        last_activity: analysis.last_activity,
+        language_lines: analysis.language_lines.clone(),
+        contributors: analysis
+            .contributors
+            .iter()
+            .filter(|c| !c.is_bot)
+            .map(|c| (c.name.clone(), c.email.clone(), c.commit_count))
+            .collect(),
+    }
+}
+
+fn aggregate(repositories: Vec<RepositorySummary>, failures: Vec<(String, String)>) -> OrgAnalysis {
+    let mut language_lines: HashMap<String, usize> = HashMap::new();
+    let mut contributors: HashMap<String, OrgContributor> = HashMap::new();
+
+    for repository in &repositories {
+        for (language, lines) in &repository.language_lines {
+            *language_lines.entry(language.clone()).or_insert(0) += lines;
+        }
+        // A person can be listed under several names with the same email, but
+        // each repository only counts once for them
+        let mut seen: HashSet<String> = HashSet::new();
+        for (name, email, commits) in &repository.contributors {
+            let key = email.to_lowercase();
+            let contributor = contributors
+                .entry(key.clone())
+                .or_insert_with(|| OrgContributor {
+                    name: name.clone(),
+                    email: email.clone(),
+                    repositories: 0,
+                    commit_count: 0,
+                });
+            if seen.insert(key) {
+                contributor.repositories += 1;
+            }
+            contributor.commit_count += commits;
+        }
+    }
+
+    let mut contributors: Vec<OrgContributor> = contributors.into_values().collect();
+    contributors.sort_by(|a, b| {
+        b.repositories
+            .cmp(&a.repositories)
+            .then(b.commit_count.cmp(&a.commit_count))
+            .then(a.name.cmp(&b.name))
+    });
+
+    OrgAnalysis {
+        total_lines: repositories.iter().map(|r| r.total_lines).sum(),
+        code_lines: repositories.iter().map(|r| r.code_lines).sum(),
+        commit_count: repositories.iter().map(|r| r.commit_count).sum(),
+        repositories,
+        failures,
+        language_lines,
+        contributors,
+    }
+}
@@ -1,6 +1,12 @@
 use clap::Parser;
 use std::path::PathBuf;
 
+use chrono::{DateTime, FixedOffset};
+
+use crate::analyzer::ComponentMode;
+use crate::git::{self, MergeStrategy};
+use crate::report::DisplayTimeZone;
+
 #[derive(Parser, Debug)]
 #[command(
     name = "repo-analyzer",
@@ -10,7 +16,7 @@ use std::path::PathBuf;
 )]
 pub struct Cli {
     /// Path to the repository to analyze
-    #[arg(short, long, required_unless_present = "remote_url")]
+    #[arg(short, long, required_unless_present_any = ["remote_url", "batch"])]
     pub repo_path: Option<PathBuf>,
 
     /// Output format (text, json, html)
@@ -33,10 +39,119 @@ pub struct Cli {
     #[arg(short = 'u', long)]
     pub remote_url: Option<String>,
 
+    /// Analyze every repository path or URL listed in this file, one per line ("-" reads stdin)
+    #[arg(
+        long,
+        value_name = "MANIFEST",
+        conflicts_with_all = ["repo_path", "remote_url", "coupling_graph", "require_signed", "upload"]
+    )]
+    pub batch: Option<String>,
+
+    /// Number of repositories analyzed at the same time in batch mode
+    #[arg(long, default_value = "4")]
+    pub jobs: usize,
+
+    /// Directory for the per-repository and organization reports of a batch
+    #[arg(long, default_value = "reports")]
+    pub batch_output: PathBuf,
+
+    /// Number of commits to fetch when cloning a remote repository (0 for the full history)
+    #[arg(long, default_value = "0")]
+    pub clone_depth: u32,
+
+    /// Branch to check out when cloning a remote repository
+    #[arg(long)]
+    pub clone_branch: Option<String>,
+
+    /// Keep remote clones in this directory and update them on later runs
+    #[arg(long)]
+    pub cache_dir: Option<PathBuf>,
+
     /// Depth of commit history to analyze (0 for all)
     #[arg(long, default_value = "0")]
     pub history_depth: usize,
 
+    /// Only analyze commits at or after this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long, value_parser = git::parse_date)]
+    pub since: Option<DateTime<FixedOffset>>,
+
+    /// Only analyze commits up to and including this day (YYYY-MM-DD), or
+    /// before this time (RFC 3339)
+    #[arg(long, value_parser = git::parse_until_date)]
+    pub until: Option<DateTime<FixedOffset>>,
+
+    /// Only analyze commits in a revision range (e.g. v1.0..main)
+    #[arg(long)]
+    pub rev_range: Option<String>,
+
+    /// Analyze the history of a branch instead of HEAD (repeatable)
+    #[arg(long = "branch")]
+    pub branches: Vec<String>,
+
+    /// Analyze the history of all refs
+    #[arg(long)]
+    pub all: bool,
+
+    /// Follow only the first parent of merge commits
+    #[arg(long)]
+    pub first_parent: bool,
+
+    /// Compute per-file and per-directory code ownership with git blame
+    #[arg(long)]
+    pub blame: bool,
+
+    /// Months without commits after which an author counts as inactive
+    #[arg(long, default_value = "6")]
+    pub inactive_months: u32,
+
+    /// Days without commits after which an unmerged branch counts as stale
+    #[arg(long, default_value = "90")]
+    pub stale_days: u32,
+
+    /// Analyze each checked out submodule as a nested report
+    #[arg(long)]
+    pub recurse_submodules: bool,
+
+    /// Keep submodule files out of the top-level statistics
+    #[arg(long)]
+    pub exclude_submodules: bool,
+
+    /// Only analyze paths matching this glob, e.g. 'services/payments/**' (repeatable)
+    #[arg(long = "include", value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    /// Leave paths matching this glob out of every analysis (repeatable)
+    #[arg(long = "exclude", value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Break metrics down per component: top-level directories or detected packages
+    #[arg(long, value_name = "MODE")]
+    pub components: Option<ComponentMode>,
+
+    /// Exclude bot accounts from contributors, churn and ownership metrics
+    #[arg(long)]
+    pub exclude_bots: bool,
+
+    /// Similarity percentage used to detect renamed and copied files (0 to disable)
+    #[arg(long, default_value = "50", value_parser = clap::value_parser!(u16).range(0..=100))]
+    pub rename_threshold: u16,
+
+    /// How merge commits are counted (skip, first-parent, combined)
+    #[arg(long, default_value = "first-parent")]
+    pub merge_strategy: MergeStrategy,
+
+    /// Write the change coupling graph to this file in Graphviz DOT format
+    #[arg(long)]
+    pub coupling_graph: Option<PathBuf>,
+
+    /// Fail when any commit in this revision range (e.g. origin/main..HEAD) is unsigned
+    #[arg(long, value_name = "RANGE")]
+    pub require_signed: Option<String>,
+
+    /// Time zone for dates in text and HTML reports (local, utc, original or an offset like +02:00)
+    #[arg(long = "timezone", default_value = "local")]
+    pub time_zone: DisplayTimeZone,
+
     /// Upload report to cloud storage
     #[arg(short = 'U', long)]
     pub upload: bool,
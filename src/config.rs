@@ -2,14 +2,59 @@ use anyhow::{Context, Result};
 use serde::Deserialize;
 use std::fs::File;
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct Config {
     #[serde(default)]
     pub api_key: Option<String>,
     #[serde(default)]
     pub api_url: Option<String>,
+    /// Extra regex patterns identifying bot accounts, matched against "Name <email>"
+    #[serde(default)]
+    pub bot_patterns: Vec<String>,
+    /// Extra regex patterns identifying issue references in commit messages, e.g. JIRA keys
+    #[serde(default)]
+    pub issue_patterns: Vec<String>,
+    /// Glob patterns scoping every analysis, added to those given with --include
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Glob patterns left out of every analysis, added to those given with --exclude
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Credentials for cloning private remote repositories
+    #[serde(default)]
+    pub auth: AuthConfig,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// Offer keys loaded in the SSH agent
+    pub ssh_agent: bool,
+    /// Private SSH key files to offer, in order
+    pub ssh_keys: Vec<PathBuf>,
+    /// Environment variable holding the passphrase of encrypted SSH keys
+    pub ssh_passphrase_env: Option<String>,
+    /// User name for remotes whose URL names none
+    pub username: Option<String>,
+    /// Environment variable holding an HTTPS token or password
+    pub token_env: String,
+    /// Ask the git credential helpers configured for the user
+    pub credential_helper: bool,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        AuthConfig {
+            ssh_agent: true,
+            ssh_keys: Vec::new(),
+            ssh_passphrase_env: None,
+            username: None,
+            token_env: "REPO_ANALYZER_GIT_TOKEN".to_string(),
+            credential_helper: true,
+        }
+    }
 }
 
 impl Config {
@@ -30,6 +75,7 @@ impl Config {
         Ok(Config {
             api_key: std::env::var("REPO_ANALYZER_API_KEY").ok(),
             api_url: std::env::var("REPO_ANALYZER_API_URL").ok(),
+            ..Config::default()
         })
     }
 }
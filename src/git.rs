@@ -1,24 +1,35 @@
 use anyhow::{Context, Result};
-use chrono::{DateTime, Local, TimeZone};
-use git2::{build::RepoBuilder, FetchOptions, RemoteCallbacks, Repository, Time};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, TimeZone};
+use git2::{
+    build::RepoBuilder, AttrCheckFlags, AttrValue, BlameOptions, BranchType, Cred, CredentialType,
+    Delta, DiffFindOptions, FetchOptions, ObjectType, Oid, RemoteCallbacks, Repository, Sort, Time,
+    TreeWalkMode, TreeWalkResult,
+};
+use regex::Regex;
 use std::collections::{HashMap, HashSet};
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 #[derive(Debug, Clone)]
 pub struct Contributor {
     pub name: String,
     pub email: String,
-    pub commit_count: usize,
-    pub first_commit: String,
-    pub last_commit: String,
+    pub commit_count: usize,      // Commits authored
+    pub co_authored_count: usize, // Commits credited via Co-authored-by
+    pub reviewed_count: usize,    // Commits credited via Reviewed-by
+    pub signed_off_count: usize,  // Commits credited via Signed-off-by
+    pub committed_count: usize,   // Commits where this person was the committer
+    pub is_bot: bool,
+    pub first_commit: DateTime<FixedOffset>,
+    pub last_commit: DateTime<FixedOffset>,
 }
 
 #[derive(Debug, Clone)]
 pub struct FileStats {
     pub commit_count: usize,
-    pub first_commit_date: String,
-    pub last_commit_date: String,
+    pub first_commit_date: DateTime<FixedOffset>,
+    pub last_commit_date: DateTime<FixedOffset>,
     pub authors: Vec<String>,
     pub lines_added: usize,
     pub lines_removed: usize,
@@ -26,10 +37,601 @@ pub struct FileStats {
     pub author_contributions: HashMap<String, usize>, // Author -> commit count
     pub last_modified_by: String,
     pub avg_changes_per_commit: f64,
+    pub deleted: bool, // The most recent change to this path deleted it
+}
+
+/// Average length of a month, used for per-month rates
+const SECONDS_PER_MONTH: f64 = 30.44 * 24.0 * 60.0 * 60.0;
+
+/// Options controlling how the commit history is walked
+#[derive(Debug, Clone)]
+pub struct HistoryOptions {
+    pub depth: usize,             // Number of commits to analyze (0 for all)
+    pub bot_patterns: Vec<Regex>, // Matched against "Name <email>"
+    pub exclude_bots: bool,       // Drop bot accounts from contributors and churn
+    pub rename_threshold: u16,    // Similarity percentage for rename/copy detection (0 to disable)
+    pub merge_strategy: MergeStrategy,
+    pub since: Option<DateTime<FixedOffset>>, // Only commits at or after this time
+    pub until: Option<DateTime<FixedOffset>>, // Only commits before this time
+    pub rev_range: Option<String>,            // e.g. "v1.0..main"
+    pub branches: Vec<String>,                // Branches to walk instead of HEAD
+    pub all_refs: bool,                       // Walk every ref instead of HEAD
+    pub first_parent: bool,                   // Follow only the first parent of merges
+    pub path_filter: PathFilter,              // Only files in scope count towards churn
+}
+
+impl Default for HistoryOptions {
+    fn default() -> Self {
+        HistoryOptions {
+            depth: 0,
+            bot_patterns: default_bot_patterns(),
+            exclude_bots: false,
+            rename_threshold: 50,
+            merge_strategy: MergeStrategy::FirstParent,
+            since: None,
+            until: None,
+            rev_range: None,
+            branches: Vec::new(),
+            all_refs: false,
+            first_parent: false,
+            path_filter: PathFilter::default(),
+        }
+    }
+}
+
+/// Glob patterns scoping an analysis to part of a repository, matched against
+/// paths relative to the repository root. Patterns without a '/' match at any
+/// depth, and a pattern matching a directory covers everything beneath it
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    include: Vec<Regex>, // Empty to include every path
+    exclude: Vec<Regex>,
+}
+
+impl PathFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        let compile = |globs: &[String]| -> Result<Vec<Regex>> {
+            globs.iter().map(|glob| glob_to_regex(glob)).collect()
+        };
+        Ok(PathFilter {
+            include: compile(include)?,
+            exclude: compile(exclude)?,
+        })
+    }
+
+    /// Whether any include or exclude pattern is set
+    pub fn is_active(&self) -> bool {
+        !self.include.is_empty() || !self.exclude.is_empty()
+    }
+
+    /// Whether a file is in scope
+    pub fn matches(&self, relative_path: &Path) -> bool {
+        let path = normalized_path(relative_path);
+        (self.include.is_empty() || self.include.iter().any(|glob| glob.is_match(&path)))
+            && !self.exclude.iter().any(|glob| glob.is_match(&path))
+    }
+
+    /// Whether a whole directory is out of scope, so walks can skip it
+    pub fn excludes_dir(&self, relative_path: &Path) -> bool {
+        let path = normalized_path(relative_path);
+        !path.is_empty() && self.exclude.iter().any(|glob| glob.is_match(&path))
+    }
+}
+
+fn normalized_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Translate a glob into an anchored regex: `*` and `?` stay within a path
+/// segment, `**` crosses segments, and `[...]` and `{a,b}` work as in shells
+fn glob_to_regex(glob: &str) -> Result<Regex> {
+    let trimmed = glob.trim_start_matches("./").trim_end_matches('/');
+    let chars: Vec<char> = trimmed.chars().collect();
+
+    let mut pattern = String::from("^");
+    if !trimmed.contains('/') {
+        pattern.push_str("(?:.*/)?");
+    }
+
+    let mut in_braces = false;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                if chars.get(i + 2) == Some(&'/') {
+                    pattern.push_str("(?:.*/)?");
+                    i += 3;
+                } else {
+                    pattern.push_str(".*");
+                    i += 2;
+                }
+                continue;
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            '[' => match chars[i + 1..].iter().position(|c| *c == ']') {
+                Some(length) => {
+                    let class: String = chars[i + 1..i + 1 + length].iter().collect();
+                    let class = match class.strip_prefix('!') {
+                        Some(negated) => format!("^{}", negated),
+                        None => class,
+                    };
+                    pattern.push_str(&format!("[{}]", class.replace('\\', "\\\\")));
+                    i += length + 2;
+                    continue;
+                }
+                None => pattern.push_str(r"\["),
+            },
+            '{' if !in_braces => {
+                in_braces = true;
+                pattern.push_str("(?:");
+            }
+            '}' if in_braces => {
+                in_braces = false;
+                pattern.push(')');
+            }
+            ',' if in_braces => pattern.push('|'),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    pattern.push_str("(?:/.*)?$");
+
+    Regex::new(&pattern).with_context(|| format!("Invalid path pattern: {}", glob))
+}
+
+/// How file changes introduced by merge commits are counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Merge commits contribute no file changes
+    Skip,
+    /// Merge commits are diffed against their first parent only
+    FirstParent,
+    /// Only files that differ from every parent are counted, like `git diff --cc`
+    Combined,
+}
+
+impl FromStr for MergeStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "skip" => Ok(MergeStrategy::Skip),
+            "first-parent" => Ok(MergeStrategy::FirstParent),
+            "combined" => Ok(MergeStrategy::Combined),
+            _ => Err(format!(
+                "Unknown merge strategy: {} (expected skip, first-parent or combined)",
+                s
+            )),
+        }
+    }
+}
+
+/// Lines changed in a single file by a commit
+#[derive(Debug, Clone)]
+struct FileChange {
+    path: PathBuf, // Path at HEAD, following renames
+    lines_added: usize,
+    lines_removed: usize,
+    deleted: bool,
+}
+
+/// Result of walking the commit history of a repository
+#[derive(Debug, Clone)]
+pub struct GitHistory {
+    pub commit_count: usize,
+    pub contributors: Vec<Contributor>,
+    pub last_activity: Option<DateTime<FixedOffset>>,
+    pub file_stats: HashMap<PathBuf, FileStats>,
+    pub commits: Vec<CommitRecord>, // Newest first
+}
+
+/// Per-commit data retained from the history walk
+#[derive(Debug, Clone)]
+pub struct CommitRecord {
+    pub id: String,
+    pub author: String,
+    pub author_time: DateTime<FixedOffset>, // In the author's own UTC offset
+    pub message: String,
+    pub files: Vec<PathBuf>, // Files changed, at the path they have at HEAD
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub signature: Option<SignatureKind>, // None for unsigned commits
+    pub merge: Option<MergeRecord>,       // Set for merge commits
+}
+
+/// The branch a merge commit brought in, through its second parent
+#[derive(Debug, Clone)]
+pub struct MergeRecord {
+    pub merge_base_time: DateTime<FixedOffset>, // Committer time of the merge base
+    pub merge_time: DateTime<FixedOffset>,      // Committer time of the merge
+    pub branch_commits: usize,                  // Commits merged in, not yet on the first parent
+}
+
+/// The kind of cryptographic signature attached to a commit. Signatures are
+/// detected, not verified, as verification needs the signers' public keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignatureKind {
+    Gpg,
+    Ssh,
+    X509,
+    Unknown,
+}
+
+impl SignatureKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SignatureKind::Gpg => "GPG",
+            SignatureKind::Ssh => "SSH",
+            SignatureKind::X509 => "X.509",
+            SignatureKind::Unknown => "Unknown",
+        }
+    }
+}
+
+/// A tag pointing at a commit
+#[derive(Debug, Clone)]
+pub struct Tag {
+    pub name: String,
+    pub commit_id: String,
+    pub date: DateTime<FixedOffset>, // Tagger date for annotated tags, commit date otherwise
+    pub annotated: bool,
+}
+
+/// A local or remote-tracking branch compared with the default branch
+#[derive(Debug, Clone)]
+pub struct BranchInfo {
+    pub name: String, // e.g. "feature" or "origin/feature"
+    pub remote: bool,
+    pub is_default: bool,
+    pub last_commit: DateTime<FixedOffset>,
+    pub last_author: String,
+    pub ahead: usize,  // Commits not on the default branch
+    pub behind: usize, // Default branch commits missing from the branch
+    pub merged: bool,  // Tip reachable from the default branch
+}
+
+/// A submodule declared in `.gitmodules`
+#[derive(Debug, Clone)]
+pub struct SubmoduleInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub url: Option<String>,
+    pub pinned_commit: Option<String>, // Commit recorded in the superproject's HEAD
+    pub initialized: bool,             // Whether it is checked out
+}
+
+/// The way a person is credited for a commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ContributionRole {
+    Authored,
+    CoAuthored,
+    Reviewed,
+    SignedOff,
+    Committed,
+}
+
+/// Lines surviving at HEAD, attributed to the authors who last changed them
+#[derive(Debug, Clone, Default)]
+pub struct Ownership {
+    pub total_lines: usize,
+    pub lines_by_author: HashMap<String, usize>, // Author -> surviving lines
+}
+
+impl Ownership {
+    /// The author owning the most lines, with their share in percent
+    pub fn primary_owner(&self) -> Option<(&str, f64)> {
+        self.lines_by_author
+            .iter()
+            .max_by(|(a_name, a), (b_name, b)| a.cmp(b).then(b_name.cmp(a_name)))
+            .map(|(author, lines)| (author.as_str(), self.percentage(*lines)))
+    }
+
+    /// Authors sorted by the number of lines they own
+    pub fn authors_by_lines(&self) -> Vec<(&str, usize, f64)> {
+        let mut authors: Vec<(&str, usize, f64)> = self
+            .lines_by_author
+            .iter()
+            .map(|(author, lines)| (author.as_str(), *lines, self.percentage(*lines)))
+            .collect();
+        authors.sort_by(|(a_name, a, _), (b_name, b, _)| b.cmp(a).then(a_name.cmp(b_name)));
+        authors
+    }
+
+    fn percentage(&self, lines: usize) -> f64 {
+        if self.total_lines == 0 {
+            0.0
+        } else {
+            (lines as f64 / self.total_lines as f64) * 100.0
+        }
+    }
+
+    fn add(&mut self, author: &str, lines: usize) {
+        self.total_lines += lines;
+        *self.lines_by_author.entry(author.to_string()).or_insert(0) += lines;
+    }
+}
+
+/// Blame-based ownership of the files and directories at HEAD
+#[derive(Debug, Clone, Default)]
+pub struct BlameOwnership {
+    pub files: HashMap<PathBuf, Ownership>,
+    pub directories: HashMap<PathBuf, Ownership>, // Includes the repository root
+}
+
+/// Linguist overrides set in .gitattributes, read through libgit2 so nested
+/// attribute files and macros are honored
+pub struct LinguistAttributes {
+    repo: Option<Repository>, // None outside a git repository
+}
+
+impl LinguistAttributes {
+    pub fn open(repo_path: &Path) -> Self {
+        LinguistAttributes {
+            repo: Repository::open(repo_path).ok(),
+        }
+    }
+
+    /// Whether an attribute such as `linguist-generated` is set (true) or
+    /// unset with '-' or '=false' (false) for a path relative to the root
+    pub fn get(&self, relative_path: &Path, name: &str) -> Option<bool> {
+        let repo = self.repo.as_ref()?;
+        let value = repo
+            .get_attr(relative_path, name, AttrCheckFlags::FILE_THEN_INDEX)
+            .ok()?;
+        match AttrValue::from_string(value) {
+            AttrValue::True => Some(true),
+            AttrValue::False => Some(false),
+            AttrValue::String(value) => match value {
+                "true" => Some(true),
+                "false" => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// A blob as first committed anywhere in the history
+#[derive(Debug, Clone)]
+pub struct HistoryBlob {
+    pub id: String,
+    pub path: PathBuf,     // Path the blob was introduced at
+    pub size: u64,         // Bytes, uncompressed
+    pub commit_id: String, // Commit introducing the blob
+    pub is_binary: bool,
+    pub at_head: bool, // Whether HEAD still contains the blob
+}
+
+/// Sizes of every blob reachable from any ref
+#[derive(Debug, Clone, Default)]
+pub struct BlobHistory {
+    pub blob_count: usize,
+    pub total_size: u64,
+    pub largest_blobs: Vec<HistoryBlob>, // Largest first
+    pub size_by_extension: Vec<(String, u64, usize)>, // Extension, bytes and blobs, largest first
+}
+
+/// How a remote repository is cloned for analysis
+#[derive(Debug, Clone, Default)]
+pub struct CloneOptions {
+    pub depth: u32,                 // Number of commits to fetch (0 for the full history)
+    pub branch: Option<String>,     // Branch to check out instead of the remote HEAD
+    pub cache_dir: Option<PathBuf>, // Keep clones here, keyed by URL, and update them on later runs
+    pub auth: CloneAuth,
+}
+
+/// Credentials offered to private remotes, tried in order: SSH agent and key
+/// files for SSH URLs, token then git credential helpers for HTTPS URLs
+#[derive(Debug, Clone, Default)]
+pub struct CloneAuth {
+    pub ssh_agent: bool,
+    pub ssh_keys: Vec<PathBuf>, // Private keys; public keys are derived by libgit2
+    pub ssh_passphrase: Option<String>, // Passphrase for encrypted key files
+    pub username: Option<String>, // Used when the URL names no user
+    pub token: Option<String>,  // HTTPS password or personal access token
+    pub credential_helper: bool,
+}
+
+/// A way of authenticating, so each one is offered to a remote only once
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum AuthMethod {
+    SshAgent,
+    SshKey(PathBuf),
+    Token,
+    CredentialHelper,
+    Username,
+    Default,
+}
+
+/// A local clone of a remote repository. Temporary clones are deleted when
+/// dropped, including when the analysis fails; cached clones are kept
+#[derive(Debug)]
+pub struct ClonedRepository {
+    pub path: PathBuf,
+    temporary: bool,
+}
+
+impl Drop for ClonedRepository {
+    fn drop(&mut self) {
+        if self.temporary && self.path.exists() {
+            if let Err(e) = std::fs::remove_dir_all(&self.path) {
+                println!(
+                    "Warning: Failed to remove temporary clone {}: {}",
+                    self.path.display(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// Clones a remote URL, `file://` URL or local path (such as a mirror) for
+/// analysis, reusing and updating the cached clone when a cache directory is set
+pub fn clone_remote(url: &str, options: &CloneOptions) -> Result<ClonedRepository> {
+    let cache_dir = match &options.cache_dir {
+        Some(cache_dir) => cache_dir,
+        None => {
+            // Guard the directory before cloning so a failed clone is removed too
+            let clone = ClonedRepository {
+                path: std::env::temp_dir().join(format!("repo-analyzer-{}", rand::random::<u32>())),
+                temporary: true,
+            };
+            clone_repository(url, &clone.path, options)?;
+            return Ok(clone);
+        }
+    };
+
+    let path = cache_dir.join(cache_key(url)?);
+    if path.exists() {
+        println!("Updating cached clone in {}", path.display());
+        update_repository(&path, url, options)?;
+    } else {
+        std::fs::create_dir_all(cache_dir)
+            .with_context(|| format!("Failed to create cache directory {}", cache_dir.display()))?;
+        if let Err(e) = clone_repository(url, &path, options) {
+            // Never leave a partial clone behind in the cache
+            std::fs::remove_dir_all(&path).unwrap_or(());
+            return Err(e);
+        }
+    }
+
+    Ok(ClonedRepository {
+        path,
+        temporary: false,
+    })
 }
 
-pub fn clone_repository(url: &str, target_path: &Path) -> Result<Repository> {
+pub fn clone_repository(
+    url: &str,
+    target_path: &Path,
+    options: &CloneOptions,
+) -> Result<Repository> {
+    let mut builder = RepoBuilder::new();
+    builder.fetch_options(fetch_options(options));
+    if let Some(branch) = &options.branch {
+        builder.branch(branch);
+    }
+
+    let repo = builder
+        .clone(url, target_path)
+        .context("Failed to clone repository")?;
+
+    println!("\nRepository cloned successfully");
+    Ok(repo)
+}
+
+/// Fetches the requested branch, or the remote HEAD, into a cached clone and
+/// checks it out. The clone must have been made from `url`
+fn update_repository(repo_path: &Path, url: &str, options: &CloneOptions) -> Result<()> {
+    let repo = Repository::open(repo_path).context("Failed to open cached clone")?;
+    let mut remote = repo
+        .find_remote("origin")
+        .context("Failed to find origin remote of cached clone")?;
+    if remote.url() != Some(url) {
+        return Err(anyhow::anyhow!(
+            "Cached clone {} was cloned from {}, not {}",
+            repo_path.display(),
+            remote.url().unwrap_or("an unknown URL"),
+            url
+        ));
+    }
+
+    let refspec = match &options.branch {
+        Some(branch) => format!("refs/heads/{}", branch),
+        None => "HEAD".to_string(),
+    };
+    remote
+        .fetch(&[&refspec], Some(&mut fetch_options(options)), None)
+        .context("Failed to update cached clone")?;
+    println!();
+
+    let target = repo
+        .refname_to_id("FETCH_HEAD")
+        .context("Failed to resolve fetched commit")?;
+    repo.set_head_detached(target)
+        .context("Failed to update HEAD of cached clone")?;
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+        .context("Failed to check out cached clone")?;
+
+    Ok(())
+}
+
+/// The first method allowed by the remote that has not been offered yet, in
+/// the order SSH agent, key files, token, credential helper, username and
+/// default credentials. It is recorded in `tried` so it is never offered twice
+fn next_auth_method(
+    auth: &CloneAuth,
+    allowed: CredentialType,
+    tried: &mut Vec<AuthMethod>,
+) -> Option<AuthMethod> {
+    let mut methods = Vec::new();
+    if allowed.contains(CredentialType::SSH_KEY) {
+        if auth.ssh_agent {
+            methods.push(AuthMethod::SshAgent);
+        }
+        methods.extend(auth.ssh_keys.iter().cloned().map(AuthMethod::SshKey));
+    }
+    if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+        if auth.token.is_some() {
+            methods.push(AuthMethod::Token);
+        }
+        if auth.credential_helper {
+            methods.push(AuthMethod::CredentialHelper);
+        }
+    }
+    if allowed.contains(CredentialType::USERNAME) {
+        methods.push(AuthMethod::Username);
+    }
+    if allowed.contains(CredentialType::DEFAULT) {
+        methods.push(AuthMethod::Default);
+    }
+
+    let method = methods.into_iter().find(|method| !tried.contains(method))?;
+    tried.push(method.clone());
+    Some(method)
+}
+
+fn fetch_options(options: &CloneOptions) -> FetchOptions<'_> {
     let mut callbacks = RemoteCallbacks::new();
+
+    // libgit2 calls back after every rejected credential, so each method is
+    // offered once and the clone fails once all of them have been tried
+    let auth = &options.auth;
+    let mut tried: Vec<AuthMethod> = Vec::new();
+    callbacks.credentials(move |url, username_from_url, allowed| {
+        let username = username_from_url
+            .map(|name| name.to_string())
+            .or_else(|| auth.username.clone())
+            .unwrap_or_else(|| "git".to_string());
+
+        while let Some(method) = next_auth_method(auth, allowed, &mut tried) {
+            let credential = match &method {
+                AuthMethod::SshAgent => Cred::ssh_key_from_agent(&username),
+                AuthMethod::SshKey(path) => {
+                    Cred::ssh_key(&username, None, path, auth.ssh_passphrase.as_deref())
+                }
+                AuthMethod::Token => {
+                    Cred::userpass_plaintext(&username, auth.token.as_deref().unwrap_or(""))
+                }
+                AuthMethod::CredentialHelper => git2::Config::open_default()
+                    .and_then(|config| Cred::credential_helper(&config, url, username_from_url)),
+                AuthMethod::Username => Cred::username(&username),
+                AuthMethod::Default => Cred::default(),
+            };
+            // Methods that cannot even produce a credential, such as a missing
+            // agent, are skipped in favour of the next one
+            if let Ok(credential) = credential {
+                return Ok(credential);
+            }
+        }
+
+        Err(git2::Error::from_str(&format!(
+            "No more credentials to try for {}",
+            url
+        )))
+    });
+
     callbacks.transfer_progress(|stats| {
         if stats.received_objects() == stats.total_objects() {
             print!(
@@ -53,198 +655,248 @@ pub fn clone_repository(url: &str, target_path: &Path) -> Result<Repository> {
 
     let mut fetch_options = FetchOptions::new();
     fetch_options.remote_callbacks(callbacks);
+    if options.depth > 0 {
+        fetch_options.depth(options.depth as i32);
+    }
+    fetch_options
+}
 
-    let repo = RepoBuilder::new()
-        .fetch_options(fetch_options)
-        .clone(url, target_path)
-        .context("Failed to clone repository")?;
+/// Directory name for a cached clone, stable across runs. The readable slug is
+/// followed by a hash of the whole URL, as different URLs can share a slug
+pub(crate) fn cache_key(url: &str) -> Result<String> {
+    let slug = url_slug(url);
+    if slug == "." || slug == ".." {
+        return Err(anyhow::anyhow!(
+            "Cannot derive a cache directory name from {}",
+            url
+        ));
+    }
 
-    println!("\nRepository cloned successfully");
-    Ok(repo)
+    let hash = Oid::hash_object(ObjectType::Blob, url.as_bytes())
+        .context("Failed to hash repository URL")?
+        .to_string();
+    Ok(if slug.is_empty() {
+        hash[..8].to_string()
+    } else {
+        format!("{}-{}", slug, &hash[..8])
+    })
+}
+
+/// Readable name for a URL, with everything but letters, digits, '.', '_'
+/// and '-' replaced by '-'
+fn url_slug(url: &str) -> String {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let key: String = without_scheme
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    key.trim_matches('-').to_string()
 }
 
 pub fn analyze_git_repo(
     repo_path: &Path,
     depth: usize,
-) -> Result<(usize, Vec<Contributor>, String)> {
-    let (commit_count, contributors, last_activity, _) =
-        analyze_git_repo_extended(repo_path, depth)?;
-    Ok((commit_count, contributors, last_activity))
+) -> Result<(usize, Vec<Contributor>, Option<DateTime<FixedOffset>>)> {
+    let options = HistoryOptions {
+        depth,
+        ..HistoryOptions::default()
+    };
+    let history = analyze_git_repo_extended(repo_path, &options)?;
+    Ok((
+        history.commit_count,
+        history.contributors,
+        history.last_activity,
+    ))
 }
 
-pub fn analyze_git_repo_extended(
-    repo_path: &Path,
-    depth: usize,
-) -> Result<(usize, Vec<Contributor>, String, HashMap<PathBuf, FileStats>)> {
+pub fn analyze_git_repo_extended(repo_path: &Path, options: &HistoryOptions) -> Result<GitHistory> {
     let repo = Repository::open(repo_path).context("Failed to open git repository")?;
 
-    let mut commit_count = 0;
+    let mut walked = 0; // Commits counted against the depth limit
+    let mut commit_count = 0; // Commits analyzed, excluding bot commits when requested
     let mut contributors_map: HashMap<String, Contributor> = HashMap::new();
-    let mut last_commit_time = None;
+    let mut last_activity: Option<DateTime<FixedOffset>> = None;
     let mut file_stats: HashMap<PathBuf, FileStats> = HashMap::new();
+    let mut commits: Vec<CommitRecord> = Vec::new();
 
-    // Get the HEAD reference
-    let head = repo.head().context("Failed to get HEAD reference")?;
-
-    // Get the commit that HEAD points to
-    let obj = head
-        .peel(git2::ObjectType::Commit)
-        .context("Failed to peel to commit")?;
-    let commit = obj
-        .into_commit()
-        .map_err(|_| anyhow::anyhow!("Failed to convert to commit"))?;
+    // Maps paths a file had in the past to the path it has at HEAD, so older
+    // commits touching a renamed file are attributed to its current path
+    let mut renamed_to: HashMap<PathBuf, PathBuf> = HashMap::new();
 
     // Create a revwalk to iterate through the commit history
-    let mut revwalk = repo.revwalk().context("Failed to create revwalk")?;
-    revwalk
-        .push(commit.id())
-        .context("Failed to push commit to revwalk")?;
+    let revwalk = build_revwalk(&repo, options)?;
 
-    for (i, oid_result) in revwalk.enumerate() {
+    for oid_result in revwalk {
         // If depth is set and we've reached it, break
-        if depth > 0 && i >= depth {
+        if options.depth > 0 && walked >= options.depth {
             break;
         }
 
         let oid = oid_result.context("Failed to get commit OID")?;
         let commit = repo.find_commit(oid).context("Failed to find commit")?;
 
-        commit_count += 1;
+        // Skip commits outside the requested date window
+        let time = commit.time();
+        if !in_date_window(&time, options) {
+            continue;
+        }
+
+        // Commits touching no file in scope are left out entirely
+        let mut scoped_changes = None;
+        if options.path_filter.is_active() {
+            let changes: Vec<FileChange> = diff_commit(&repo, &commit, options, &mut renamed_to)?
+                .into_iter()
+                .filter(|change| options.path_filter.matches(&change.path))
+                .collect();
+            if changes.is_empty() {
+                continue;
+            }
+            scoped_changes = Some(changes);
+        }
+
+        walked += 1;
 
         // Get commit author
         let author = commit.author();
-        let time = commit.time();
-        let datetime = format_git_time(&time);
+        let datetime = to_datetime(&time);
 
         // Update last commit time
-        if last_commit_time.is_none() || time.seconds() > last_commit_time.unwrap() {
-            last_commit_time = Some(time.seconds());
+        if !matches!(last_activity, Some(last) if last >= datetime) {
+            last_activity = Some(datetime);
         }
 
         // Update contributor information
-        let key = format!(
-            "{} <{}>",
-            author.name().unwrap_or("Unknown"),
-            author.email().unwrap_or("unknown")
+        let author_name = author.name().unwrap_or("Unknown");
+        let author_email = author.email().unwrap_or("unknown");
+        let author_is_bot = is_bot(author_name, author_email, &options.bot_patterns);
+        credit_contributor(
+            &mut contributors_map,
+            author_name,
+            author_email,
+            ContributionRole::Authored,
+            datetime,
+            &options.bot_patterns,
         );
 
-        contributors_map
-            .entry(key.clone())
-            .and_modify(|contributor| {
-                contributor.commit_count += 1;
-                contributor.last_commit = datetime.clone();
-            })
-            .or_insert_with(|| Contributor {
-                name: author.name().unwrap_or("Unknown").to_string(),
-                email: author.email().unwrap_or("unknown").to_string(),
-                commit_count: 1,
-                first_commit: datetime.clone(),
-                last_commit: datetime.clone(),
-            });
+        let committer = commit.committer();
+        credit_contributor(
+            &mut contributors_map,
+            committer.name().unwrap_or("Unknown"),
+            committer.email().unwrap_or("unknown"),
+            ContributionRole::Committed,
+            datetime,
+            &options.bot_patterns,
+        );
+
+        // Credit people listed in the commit message trailers
+        let author_key = contributor_key(author_name, author_email);
+        let mut credited: HashSet<(String, ContributionRole)> = HashSet::new();
+        for (role, name, email) in parse_commit_trailers(commit.message().unwrap_or("")) {
+            let key = contributor_key(&name, &email);
+
+            // The author listing themselves as co-author is not a second contribution
+            if role == ContributionRole::CoAuthored && key == author_key {
+                continue;
+            }
+            if !credited.insert((key, role)) {
+                continue;
+            }
+
+            credit_contributor(
+                &mut contributors_map,
+                &name,
+                &email,
+                role,
+                datetime,
+                &options.bot_patterns,
+            );
+        }
+
+        // Bot commits do not count towards churn and ownership when excluded
+        if options.exclude_bots && author_is_bot {
+            continue;
+        }
+        commit_count += 1;
 
         // Get file changes in this commit
-        if let Ok(parent) = commit.parent(0) {
-            let diff = repo
-                .diff_tree_to_tree(
-                    Some(&parent.tree().unwrap()),
-                    Some(&commit.tree().unwrap()),
-                    None,
-                )
-                .unwrap();
-
-            let mut lines_added_map: HashMap<PathBuf, usize> = HashMap::new();
-            let mut lines_removed_map: HashMap<PathBuf, usize> = HashMap::new();
-            let mut files_changed: HashSet<PathBuf> = HashSet::new();
-
-            diff.foreach(
-                &mut |delta, _| {
-                    if let Some(path) = delta.new_file().path() {
-                        files_changed.insert(repo_path.join(path));
-                    }
-                    true
-                },
-                None,
-                Some(&mut |delta, hunk| {
-                    if let Some(path) = delta.new_file().path() {
-                        let path_buf = repo_path.join(path);
-                        *lines_added_map.entry(path_buf.clone()).or_insert(0) +=
-                            hunk.new_lines() as usize;
-                        *lines_removed_map.entry(path_buf).or_insert(0) +=
-                            hunk.old_lines() as usize;
-                    }
-                    true
-                }),
-                None,
-            )
-            .unwrap();
-
-            // Now update file_stats with the collected information
-            for path in files_changed {
-                let author_name = author.name().unwrap_or("Unknown").to_string();
-                let added = lines_added_map.get(&path).cloned().unwrap_or(0);
-                let removed = lines_removed_map.get(&path).cloned().unwrap_or(0);
-
-                // Check if we already have stats for this file
-                if let Some(stats) = file_stats.get_mut(&path) {
-                    // Update existing stats
-                    stats.commit_count += 1;
-                    stats.last_commit_date = datetime.clone();
+        let author_name = author.name().unwrap_or("Unknown").to_string();
+        let changes = match scoped_changes {
+            Some(changes) => changes,
+            None => diff_commit(&repo, &commit, options, &mut renamed_to)?,
+        };
+
+        commits.push(commit_record(&repo, repo_path, &commit, &changes)?);
+
+        for change in changes {
+            let path = repo_path.join(&change.path);
+
+            // Check if we already have stats for this file
+            if let Some(stats) = file_stats.get_mut(&path) {
+                // Update existing stats
+                stats.commit_count += 1;
+                if datetime < stats.first_commit_date {
+                    stats.first_commit_date = datetime;
+                }
+                if datetime > stats.last_commit_date {
+                    stats.last_commit_date = datetime;
                     stats.last_modified_by = author_name.clone();
-                    stats.lines_added += added;
-                    stats.lines_removed += removed;
-
-                    // Update author contributions
-                    *stats
-                        .author_contributions
-                        .entry(author_name.clone())
-                        .or_insert(0) += 1;
-
-                    if !stats.authors.contains(&author_name) {
-                        stats.authors.push(author_name);
-                    }
-                } else {
-                    // Create new stats
-                    let mut authors = Vec::new();
-                    authors.push(author_name.clone());
-
-                    let mut author_contributions = HashMap::new();
-                    author_contributions.insert(author_name.clone(), 1);
-
-                    let new_stats = FileStats {
-                        commit_count: 1,
-                        first_commit_date: datetime.clone(),
-                        last_commit_date: datetime.clone(),
-                        authors,
-                        lines_added: added,
-                        lines_removed: removed,
-                        change_frequency: 0.0,
-                        author_contributions,
-                        last_modified_by: author_name,
-                        avg_changes_per_commit: 0.0,
-                    };
+                    stats.deleted = change.deleted;
+                }
+                stats.lines_added += change.lines_added;
+                stats.lines_removed += change.lines_removed;
+
+                // Update author contributions
+                *stats
+                    .author_contributions
+                    .entry(author_name.clone())
+                    .or_insert(0) += 1;
 
-                    file_stats.insert(path, new_stats);
+                if !stats.authors.contains(&author_name) {
+                    stats.authors.push(author_name.clone());
                 }
+            } else {
+                // Create new stats
+                let authors = vec![author_name.clone()];
+
+                let mut author_contributions = HashMap::new();
+                author_contributions.insert(author_name.clone(), 1);
+
+                let new_stats = FileStats {
+                    commit_count: 1,
+                    first_commit_date: datetime,
+                    last_commit_date: datetime,
+                    authors,
+                    lines_added: change.lines_added,
+                    lines_removed: change.lines_removed,
+                    change_frequency: 0.0,
+                    author_contributions,
+                    last_modified_by: author_name.clone(),
+                    avg_changes_per_commit: 0.0,
+                    deleted: change.deleted,
+                };
+
+                file_stats.insert(path, new_stats);
             }
         }
     }
 
     // Calculate additional statistics for each file
     for stats in file_stats.values_mut() {
-        // Calculate change frequency (changes per month)
-        if let (Ok(first_date), Ok(last_date)) = (
-            chrono::DateTime::parse_from_str(&stats.first_commit_date, "%Y-%m-%d %H:%M:%S %z"),
-            chrono::DateTime::parse_from_str(&stats.last_commit_date, "%Y-%m-%d %H:%M:%S %z"),
-        ) {
-            let duration = last_date.signed_duration_since(first_date);
-            let months = (duration.num_days() as f64) / 30.0;
-
-            if months > 0.0 {
-                stats.change_frequency = stats.commit_count as f64 / months;
-            } else {
-                stats.change_frequency = stats.commit_count as f64; // All changes in less than a month
-            }
-        }
+        // Calculate change frequency (changes per month), counting spans
+        // shorter than a month as a full month
+        let duration = stats
+            .last_commit_date
+            .signed_duration_since(stats.first_commit_date);
+        let months = duration.num_seconds() as f64 / SECONDS_PER_MONTH;
+        stats.change_frequency = stats.commit_count as f64 / months.max(1.0);
 
         // Calculate average changes per commit
         let total_changes = stats.lines_added + stats.lines_removed;
@@ -253,22 +905,1317 @@ pub fn analyze_git_repo_extended(
         }
     }
 
-    // Sort contributors by commit count
-    let mut contributors: Vec<Contributor> = contributors_map.values().cloned().collect();
-    contributors.sort_by(|a, b| b.commit_count.cmp(&a.commit_count));
+    // Sort contributors by authored commits, then by co-authored commits
+    let mut contributors: Vec<Contributor> = contributors_map
+        .into_values()
+        .filter(|contributor| !(options.exclude_bots && contributor.is_bot))
+        .collect();
+    contributors.sort_by(|a, b| {
+        b.commit_count
+            .cmp(&a.commit_count)
+            .then(b.co_authored_count.cmp(&a.co_authored_count))
+    });
 
-    // Format last activity time
-    let last_activity = if let Some(time) = last_commit_time {
-        let dt = Local.timestamp_opt(time, 0).unwrap();
-        dt.format("%Y-%m-%d %H:%M:%S").to_string()
-    } else {
-        "Unknown".to_string()
-    };
+    Ok(GitHistory {
+        commit_count,
+        contributors,
+        last_activity,
+        file_stats,
+        commits,
+    })
+}
+
+/// Patterns identifying well-known automation accounts
+pub fn default_bot_patterns() -> Vec<Regex> {
+    vec![
+        Regex::new(r"\[bot\]").unwrap(),
+        Regex::new(r"(?i)dependabot").unwrap(),
+        Regex::new(r"(?i)renovate").unwrap(),
+        Regex::new(r"(?i)github-actions").unwrap(),
+        Regex::new(r"<noreply@github\.com>$").unwrap(),
+    ]
+}
+
+pub fn is_bot(name: &str, email: &str, patterns: &[Regex]) -> bool {
+    let identity = contributor_key(name, email);
+    patterns.iter().any(|pattern| pattern.is_match(&identity))
+}
+
+/// Runs blame over every text file at HEAD and aggregates the surviving lines
+/// per author for each file and each directory containing it
+pub fn analyze_blame(
+    repo_path: &Path,
+    options: &HistoryOptions,
+    skipped_files: &HashSet<PathBuf>, // Absolute paths left out of ownership
+) -> Result<BlameOwnership> {
+    let repo = Repository::open(repo_path).context("Failed to open git repository")?;
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .context("Failed to get HEAD commit")?;
+    let tree = head.tree().context("Failed to get HEAD tree")?;
+
+    // Collect the blobs first, blaming inside the tree walk callback cannot report errors
+    let mut paths: Vec<PathBuf> = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            if let Some(name) = entry.name() {
+                let is_binary = repo
+                    .find_blob(entry.id())
+                    .map(|blob| blob.is_binary())
+                    .unwrap_or(true);
+                let path = Path::new(root).join(name);
+                if !is_binary
+                    && options.path_filter.matches(&path)
+                    && !skipped_files.contains(&repo_path.join(&path))
+                {
+                    paths.push(path);
+                }
+            }
+        }
+        TreeWalkResult::Ok
+    })
+    .context("Failed to walk HEAD tree")?;
+
+    let mut ownership = BlameOwnership::default();
+
+    for path in paths {
+        let mut blame_options = BlameOptions::new();
+        blame_options.newest_commit(head.id());
+
+        let blame = repo
+            .blame_file(&path, Some(&mut blame_options))
+            .with_context(|| format!("Failed to blame {}", path.display()))?;
+
+        let mut file_ownership = Ownership::default();
+        for hunk in blame.iter() {
+            let signature = hunk.final_signature();
+            let name = signature.name().unwrap_or("Unknown");
+            let email = signature.email().unwrap_or("unknown");
+
+            // Bot lines do not count towards ownership when excluded
+            if options.exclude_bots && is_bot(name, email, &options.bot_patterns) {
+                continue;
+            }
+
+            file_ownership.add(name, hunk.lines_in_hunk());
+        }
 
-    Ok((commit_count, contributors, last_activity, file_stats))
+        // Credit the lines to every directory containing the file
+        for directory in path.ancestors().skip(1) {
+            let dir_path = if directory.as_os_str().is_empty() {
+                repo_path.to_path_buf()
+            } else {
+                repo_path.join(directory)
+            };
+            let dir_ownership = ownership.directories.entry(dir_path).or_default();
+            for (author, lines) in &file_ownership.lines_by_author {
+                dir_ownership.add(author, *lines);
+            }
+        }
+
+        ownership
+            .files
+            .insert(repo_path.join(&path), file_ownership);
+    }
+
+    Ok(ownership)
 }
 
-fn format_git_time(time: &Time) -> String {
-    let dt: DateTime<Local> = Local.timestamp_opt(time.seconds(), 0).unwrap();
-    dt.format("%Y-%m-%d %H:%M:%S").to_string()
+/// Walks every commit reachable from any ref, oldest first, recording each blob
+/// the first time a commit introduces it and keeping the `limit` largest
+pub fn analyze_blobs(
+    repo_path: &Path,
+    limit: usize,
+    path_filter: &PathFilter,
+) -> Result<BlobHistory> {
+    let repo = Repository::open(repo_path).context("Failed to open git repository")?;
+    let odb = repo.odb().context("Failed to open object database")?;
+
+    // Blobs present at HEAD, to tell deleted bloat from current files
+    let mut head_blobs: HashSet<git2::Oid> = HashSet::new();
+    if let Ok(head) = repo.head().and_then(|head| head.peel_to_tree()) {
+        head.walk(TreeWalkMode::PreOrder, |_, entry| {
+            if entry.kind() == Some(ObjectType::Blob) {
+                head_blobs.insert(entry.id());
+            }
+            TreeWalkResult::Ok
+        })
+        .context("Failed to walk HEAD tree")?;
+    }
+
+    let mut revwalk = repo.revwalk().context("Failed to create revwalk")?;
+    revwalk
+        .push_glob("*")
+        .context("Failed to push refs to revwalk")?;
+    if repo.head().is_ok() {
+        revwalk
+            .push_head()
+            .context("Failed to push HEAD to revwalk")?;
+    }
+    revwalk
+        .set_sorting(Sort::TOPOLOGICAL | Sort::TIME | Sort::REVERSE)
+        .context("Failed to set revwalk sorting")?;
+
+    let mut seen: HashSet<git2::Oid> = HashSet::new();
+    let mut blobs: Vec<HistoryBlob> = Vec::new();
+    let mut extensions: HashMap<String, (u64, usize)> = HashMap::new();
+    let mut history = BlobHistory::default();
+
+    for oid_result in revwalk {
+        let oid = oid_result.context("Failed to get commit OID")?;
+        let commit = repo.find_commit(oid).context("Failed to find commit")?;
+        let tree = commit.tree().context("Failed to get commit tree")?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree().context("Failed to get parent tree")?),
+            Err(_) => None,
+        };
+
+        let diff = repo
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+            .context("Failed to diff trees")?;
+
+        for delta in diff.deltas() {
+            if !matches!(
+                delta.status(),
+                Delta::Added | Delta::Modified | Delta::Renamed | Delta::Copied
+            ) {
+                continue;
+            }
+
+            let new_file = delta.new_file();
+            let path = new_file.path().unwrap_or(Path::new("")).to_path_buf();
+            if !path_filter.matches(&path) {
+                continue;
+            }
+            let blob_id = new_file.id();
+            if !seen.insert(blob_id) {
+                continue;
+            }
+
+            // Submodule entries point at commits of another repository
+            let size = match odb.read_header(blob_id) {
+                Ok((size, ObjectType::Blob)) => size as u64,
+                _ => continue,
+            };
+            let extension = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| ext.to_lowercase())
+                .unwrap_or_else(|| "(none)".to_string());
+
+            let totals = extensions.entry(extension).or_insert((0, 0));
+            totals.0 += size;
+            totals.1 += 1;
+            history.blob_count += 1;
+            history.total_size += size;
+
+            blobs.push(HistoryBlob {
+                id: blob_id.to_string(),
+                path: repo_path.join(path),
+                size,
+                commit_id: oid.to_string(),
+                is_binary: false,
+                at_head: head_blobs.contains(&blob_id),
+            });
+        }
+    }
+
+    // Only the reported blobs are loaded to check for binary content
+    blobs.sort_by_key(|blob| std::cmp::Reverse(blob.size));
+    blobs.truncate(limit);
+    for blob in &mut blobs {
+        let blob_id = git2::Oid::from_str(&blob.id).context("Invalid blob id")?;
+        blob.is_binary = repo
+            .find_blob(blob_id)
+            .map(|content| content.is_binary())
+            .unwrap_or(false);
+    }
+    history.largest_blobs = blobs;
+
+    history.size_by_extension = extensions
+        .into_iter()
+        .map(|(extension, (size, count))| (extension, size, count))
+        .collect();
+    history
+        .size_by_extension
+        .sort_by(|(a_ext, a, _), (b_ext, b, _)| b.cmp(a).then(a_ext.cmp(b_ext)));
+
+    Ok(history)
+}
+
+/// Lists the commits in a revision range, or reachable from a single revision,
+/// that carry no signature, as (SHA, author, subject), newest first
+pub fn find_unsigned_commits(
+    repo_path: &Path,
+    range: &str,
+) -> Result<Vec<(String, String, String)>> {
+    let repo = Repository::open(repo_path).context("Failed to open git repository")?;
+
+    let options = if range.contains("..") {
+        HistoryOptions {
+            rev_range: Some(range.to_string()),
+            ..HistoryOptions::default()
+        }
+    } else {
+        HistoryOptions {
+            branches: vec![range.to_string()],
+            ..HistoryOptions::default()
+        }
+    };
+
+    let mut unsigned = Vec::new();
+    for oid_result in build_revwalk(&repo, &options)? {
+        let oid = oid_result.context("Failed to get commit OID")?;
+        if commit_signature(&repo, oid).is_some() {
+            continue;
+        }
+
+        let commit = repo.find_commit(oid).context("Failed to find commit")?;
+        unsigned.push((
+            oid.to_string(),
+            commit.author().name().unwrap_or("Unknown").to_string(),
+            commit.summary().unwrap_or("").to_string(),
+        ));
+    }
+
+    Ok(unsigned)
+}
+
+/// Lists local and remote-tracking branches, comparing each with the default
+/// branch: the remote HEAD of `origin` when known, the checked out branch otherwise
+pub fn analyze_branches(repo_path: &Path) -> Result<(Option<String>, Vec<BranchInfo>)> {
+    let repo = Repository::open(repo_path).context("Failed to open git repository")?;
+
+    // The branch origin/HEAD points to, or the checked-out branch when that is
+    // missing or dangling. Without either, branches are not compared
+    let origin_head = repo
+        .find_reference("refs/remotes/origin/HEAD")
+        .ok()
+        .and_then(|reference| reference.symbolic_target().map(|target| target.to_string()));
+    let checked_out = repo
+        .head()
+        .ok()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.name().map(|name| name.to_string()));
+    let (default_ref, default_oid) = match [origin_head, checked_out]
+        .into_iter()
+        .flatten()
+        .find_map(|name| repo.refname_to_id(&name).ok().map(|oid| (name, oid)))
+    {
+        Some((name, oid)) => (Some(name), Some(oid)),
+        None => (None, None),
+    };
+
+    let mut branches = Vec::new();
+    for branch in repo.branches(None).context("Failed to list branches")? {
+        let (branch, branch_type) = branch.context("Failed to read branch")?;
+        let reference = branch.get();
+
+        // Skip symbolic refs such as origin/HEAD
+        if reference.symbolic_target().is_some() {
+            continue;
+        }
+
+        let name = match branch.name() {
+            Ok(Some(name)) => name.to_string(),
+            _ => continue,
+        };
+        let commit = reference
+            .peel_to_commit()
+            .with_context(|| format!("Failed to resolve branch {}", name))?;
+
+        let (ahead, behind) = match default_oid {
+            Some(default_oid) => repo
+                .graph_ahead_behind(commit.id(), default_oid)
+                .with_context(|| format!("Failed to compare branch {}", name))?,
+            None => (0, 0),
+        };
+
+        branches.push(BranchInfo {
+            is_default: reference.name() == default_ref.as_deref(),
+            remote: branch_type == BranchType::Remote,
+            last_commit: to_datetime(&commit.time()),
+            last_author: commit.author().name().unwrap_or("Unknown").to_string(),
+            ahead,
+            behind,
+            merged: default_oid.is_some() && ahead == 0,
+            name,
+        });
+    }
+
+    let default_branch = default_ref.map(|name| {
+        name.trim_start_matches("refs/heads/")
+            .trim_start_matches("refs/remotes/")
+            .to_string()
+    });
+    Ok((default_branch, branches))
+}
+
+/// Lists the submodules of a repository, without descending into nested ones
+pub fn list_submodules(repo_path: &Path) -> Result<Vec<SubmoduleInfo>> {
+    let repo = Repository::open(repo_path).context("Failed to open git repository")?;
+
+    let mut submodules = Vec::new();
+    for submodule in repo.submodules().context("Failed to list submodules")? {
+        submodules.push(SubmoduleInfo {
+            name: submodule.name().unwrap_or("").to_string(),
+            path: repo_path.join(submodule.path()),
+            url: submodule.url().map(|url| url.to_string()),
+            pinned_commit: submodule.head_id().map(|id| id.to_string()),
+            initialized: submodule.open().is_ok(),
+        });
+    }
+
+    Ok(submodules)
+}
+
+/// Lists annotated and lightweight tags pointing at commits, oldest first
+pub fn list_tags(repo_path: &Path) -> Result<Vec<Tag>> {
+    let repo = Repository::open(repo_path).context("Failed to open git repository")?;
+
+    let mut tags = Vec::new();
+    for reference in repo
+        .references_glob("refs/tags/*")
+        .context("Failed to list tags")?
+    {
+        let reference = reference.context("Failed to read tag reference")?;
+        let name = reference.shorthand().unwrap_or("").to_string();
+
+        // Tags of trees and blobs are not releases
+        let commit = match reference.peel_to_commit() {
+            Ok(commit) => commit,
+            Err(_) => continue,
+        };
+
+        let annotated_tag = reference.peel_to_tag().ok();
+        let tagger_date = annotated_tag
+            .as_ref()
+            .and_then(|tag| tag.tagger().map(|tagger| to_datetime(&tagger.when())));
+
+        tags.push(Tag {
+            name,
+            commit_id: commit.id().to_string(),
+            date: tagger_date.unwrap_or_else(|| to_datetime(&commit.time())),
+            annotated: annotated_tag.is_some(),
+        });
+    }
+
+    tags.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.name.cmp(&b.name)));
+    Ok(tags)
+}
+
+/// Creates a revwalk over the revisions selected by the options, starting
+/// from HEAD when no range, branch or `--all` is given
+fn build_revwalk<'repo>(
+    repo: &'repo Repository,
+    options: &HistoryOptions,
+) -> Result<git2::Revwalk<'repo>> {
+    let mut revwalk = repo.revwalk().context("Failed to create revwalk")?;
+
+    if let Some(range) = &options.rev_range {
+        revwalk
+            .push_range(range)
+            .with_context(|| format!("Failed to resolve revision range {}", range))?;
+    }
+
+    for branch in &options.branches {
+        let commit = repo
+            .revparse_single(branch)
+            .and_then(|obj| obj.peel_to_commit())
+            .with_context(|| format!("Failed to resolve branch {}", branch))?;
+        revwalk
+            .push(commit.id())
+            .context("Failed to push branch to revwalk")?;
+    }
+
+    if options.all_refs {
+        revwalk
+            .push_glob("*")
+            .context("Failed to push refs to revwalk")?;
+    }
+
+    if options.all_refs || (options.rev_range.is_none() && options.branches.is_empty()) {
+        revwalk
+            .push_head()
+            .context("Failed to push HEAD to revwalk")?;
+    }
+
+    if options.first_parent {
+        revwalk
+            .simplify_first_parent()
+            .context("Failed to restrict revwalk to first parents")?;
+    }
+
+    // Children must be visited before their parents for renames to be followed
+    revwalk
+        .set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+        .context("Failed to set revwalk sorting")?;
+
+    Ok(revwalk)
+}
+
+/// Records of the given commits with every changed file, whatever the path
+/// filter, bot and date options of the main analysis. Used where whole
+/// commits matter, such as the size of a release
+pub fn commit_records(
+    repo_path: &Path,
+    ids: &[String],
+    options: &HistoryOptions,
+) -> Result<Vec<CommitRecord>> {
+    let repo = Repository::open(repo_path).context("Failed to open git repository")?;
+    let mut renamed_to: HashMap<PathBuf, PathBuf> = HashMap::new();
+
+    let mut records = Vec::new();
+    for id in ids {
+        let oid = Oid::from_str(id).context("Failed to parse commit OID")?;
+        let commit = repo.find_commit(oid).context("Failed to find commit")?;
+        let changes = diff_commit(&repo, &commit, options, &mut renamed_to)?;
+        records.push(commit_record(&repo, repo_path, &commit, &changes)?);
+    }
+
+    Ok(records)
+}
+
+fn commit_record(
+    repo: &Repository,
+    repo_path: &Path,
+    commit: &git2::Commit,
+    changes: &[FileChange],
+) -> Result<CommitRecord> {
+    let author = commit.author();
+    Ok(CommitRecord {
+        id: commit.id().to_string(),
+        author: author.name().unwrap_or("Unknown").to_string(),
+        author_time: to_datetime(&author.when()),
+        message: commit.message().unwrap_or("").to_string(),
+        files: changes
+            .iter()
+            .map(|change| repo_path.join(&change.path))
+            .collect(),
+        lines_added: changes.iter().map(|change| change.lines_added).sum(),
+        lines_removed: changes.iter().map(|change| change.lines_removed).sum(),
+        signature: commit_signature(repo, commit.id()),
+        merge: merge_record(repo, commit)?,
+    })
+}
+
+/// Ids of the commits a history walk with these options visits, newest first,
+/// without diffing them. Depth and date limits are not applied
+pub fn list_commit_ids(repo_path: &Path, options: &HistoryOptions) -> Result<Vec<String>> {
+    let repo = Repository::open(repo_path).context("Failed to open git repository")?;
+    let revwalk = build_revwalk(&repo, options)?;
+
+    let mut ids = Vec::new();
+    for oid_result in revwalk {
+        let oid = oid_result.context("Failed to get commit OID")?;
+        ids.push(oid.to_string());
+    }
+
+    Ok(ids)
+}
+
+fn in_date_window(time: &Time, options: &HistoryOptions) -> bool {
+    let seconds = time.seconds();
+    let before_since = matches!(options.since, Some(since) if seconds < since.timestamp());
+    let after_until = matches!(options.until, Some(until) if seconds >= until.timestamp());
+    !before_since && !after_until
+}
+
+/// Parses a date given as `YYYY-MM-DD` (local midnight) or RFC 3339
+pub fn parse_date(value: &str) -> std::result::Result<DateTime<FixedOffset>, String> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime);
+    }
+
+    local_midnight(parse_day(value)?, value)
+}
+
+/// Parses the end of a date window. `until` is exclusive, so a bare
+/// `YYYY-MM-DD` becomes the following local midnight and includes that whole
+/// day, while an RFC 3339 time is used as given
+pub fn parse_until_date(value: &str) -> std::result::Result<DateTime<FixedOffset>, String> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime);
+    }
+
+    let next_day = parse_day(value)?
+        .succ_opt()
+        .ok_or_else(|| format!("Invalid date: {}", value))?;
+    local_midnight(next_day, value)
+}
+
+fn parse_day(value: &str) -> std::result::Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date: {} (expected YYYY-MM-DD or RFC 3339)", value))
+}
+
+fn local_midnight(
+    date: NaiveDate,
+    value: &str,
+) -> std::result::Result<DateTime<FixedOffset>, String> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|datetime| datetime.fixed_offset())
+        .ok_or_else(|| format!("Invalid local date: {}", value))
+}
+
+/// Collects the files changed by a commit. Root commits are diffed against the
+/// empty tree and merge commits according to the configured merge strategy.
+fn diff_commit(
+    repo: &Repository,
+    commit: &git2::Commit,
+    options: &HistoryOptions,
+    renamed_to: &mut HashMap<PathBuf, PathBuf>,
+) -> Result<Vec<FileChange>> {
+    let tree = commit.tree().context("Failed to get commit tree")?;
+
+    let parents: Vec<git2::Commit> = match commit.parent_count() {
+        0 => Vec::new(),
+        1 => vec![commit.parent(0)?],
+        _ => match options.merge_strategy {
+            MergeStrategy::Skip => return Ok(Vec::new()),
+            MergeStrategy::FirstParent => vec![commit.parent(0)?],
+            MergeStrategy::Combined => commit.parents().collect(),
+        },
+    };
+
+    if parents.is_empty() {
+        let (changes, _) = diff_trees(repo, None, &tree, options, renamed_to)?;
+        return Ok(changes.into_values().collect());
+    }
+
+    let mut combined: Option<HashMap<PathBuf, FileChange>> = None;
+    let mut first_parent_renames = Vec::new();
+
+    for (i, parent) in parents.iter().enumerate() {
+        let parent_tree = parent.tree().context("Failed to get parent tree")?;
+        let (changes, renames) = diff_trees(repo, Some(&parent_tree), &tree, options, renamed_to)?;
+        if i == 0 {
+            first_parent_renames = renames;
+        }
+
+        // Keep only files that differ from every parent, with the smallest change seen
+        combined = Some(match combined {
+            None => changes,
+            Some(previous) => previous
+                .into_iter()
+                .filter_map(|(path, change)| {
+                    changes.get(&path).map(|other| {
+                        let merged = FileChange {
+                            path: change.path.clone(),
+                            lines_added: change.lines_added.min(other.lines_added),
+                            lines_removed: change.lines_removed.min(other.lines_removed),
+                            deleted: change.deleted && other.deleted,
+                        };
+                        (path, merged)
+                    })
+                })
+                .collect(),
+        });
+    }
+
+    // Commits older than this one know the file under its previous path
+    renamed_to.extend(first_parent_renames);
+
+    Ok(combined.unwrap_or_default().into_values().collect())
+}
+
+/// Renames found in a diff, as (old path, path at HEAD)
+type Renames = Vec<(PathBuf, PathBuf)>;
+
+/// Diffs two trees, returning the changes keyed by the path each file has at
+/// HEAD along with the renames found in the diff
+fn diff_trees(
+    repo: &Repository,
+    old_tree: Option<&git2::Tree>,
+    new_tree: &git2::Tree,
+    options: &HistoryOptions,
+    renamed_to: &HashMap<PathBuf, PathBuf>,
+) -> Result<(HashMap<PathBuf, FileChange>, Renames)> {
+    let mut diff = repo
+        .diff_tree_to_tree(old_tree, Some(new_tree), None)
+        .context("Failed to diff commit against its parent")?;
+
+    if options.rename_threshold > 0 {
+        let mut find_options = DiffFindOptions::new();
+        find_options
+            .renames(true)
+            .copies(true)
+            .rename_threshold(options.rename_threshold)
+            .copy_threshold(options.rename_threshold);
+        diff.find_similar(Some(&mut find_options))
+            .context("Failed to detect renames")?;
+    }
+
+    let mut changes: HashMap<PathBuf, FileChange> = HashMap::new();
+    let mut renames: Renames = Vec::new();
+
+    for delta in diff.deltas() {
+        // Deleted files only have a meaningful old path
+        let deleted = delta.status() == Delta::Deleted;
+        let path = if deleted {
+            delta.old_file().path()
+        } else {
+            delta.new_file().path()
+        };
+
+        if let Some(path) = path {
+            let current_path = resolve_renamed_path(renamed_to, path);
+
+            if delta.status() == Delta::Renamed {
+                if let Some(old_path) = delta.old_file().path() {
+                    renames.push((old_path.to_path_buf(), current_path.clone()));
+                }
+            }
+
+            changes.insert(
+                path.to_path_buf(),
+                FileChange {
+                    path: current_path,
+                    lines_added: 0,
+                    lines_removed: 0,
+                    deleted,
+                },
+            );
+        }
+    }
+
+    diff.foreach(
+        &mut |_, _| true,
+        None,
+        None,
+        Some(&mut |delta, _, line| {
+            let path = if delta.status() == Delta::Deleted {
+                delta.old_file().path()
+            } else {
+                delta.new_file().path()
+            };
+
+            if let Some(change) = path.and_then(|path| changes.get_mut(path)) {
+                match line.origin() {
+                    '+' => change.lines_added += 1,
+                    '-' => change.lines_removed += 1,
+                    _ => {}
+                }
+            }
+            true
+        }),
+    )
+    .context("Failed to collect diff lines")?;
+
+    let changes = changes
+        .into_values()
+        .map(|change| (change.path.clone(), change))
+        .collect();
+
+    Ok((changes, renames))
+}
+
+/// Follows a path through the renames seen so far to the path the file has at HEAD
+fn resolve_renamed_path(renamed_to: &HashMap<PathBuf, PathBuf>, path: &Path) -> PathBuf {
+    renamed_to
+        .get(path)
+        .cloned()
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+fn contributor_key(name: &str, email: &str) -> String {
+    format!("{} <{}>", name, email)
+}
+
+fn credit_contributor(
+    contributors_map: &mut HashMap<String, Contributor>,
+    name: &str,
+    email: &str,
+    role: ContributionRole,
+    datetime: DateTime<FixedOffset>,
+    bot_patterns: &[Regex],
+) {
+    let contributor = contributors_map
+        .entry(contributor_key(name, email))
+        .or_insert_with(|| Contributor {
+            name: name.to_string(),
+            email: email.to_string(),
+            commit_count: 0,
+            co_authored_count: 0,
+            reviewed_count: 0,
+            signed_off_count: 0,
+            committed_count: 0,
+            is_bot: is_bot(name, email, bot_patterns),
+            first_commit: datetime,
+            last_commit: datetime,
+        });
+
+    if datetime < contributor.first_commit {
+        contributor.first_commit = datetime;
+    }
+    if datetime > contributor.last_commit {
+        contributor.last_commit = datetime;
+    }
+
+    match role {
+        ContributionRole::Authored => contributor.commit_count += 1,
+        ContributionRole::CoAuthored => contributor.co_authored_count += 1,
+        ContributionRole::Reviewed => contributor.reviewed_count += 1,
+        ContributionRole::SignedOff => contributor.signed_off_count += 1,
+        ContributionRole::Committed => contributor.committed_count += 1,
+    }
+}
+
+/// Extracts the people credited in the trailers of a commit message
+/// (Co-authored-by, Reviewed-by and Signed-off-by)
+fn parse_commit_trailers(message: &str) -> Vec<(ContributionRole, String, String)> {
+    let mut credits = Vec::new();
+
+    let trailers = match git2::message_trailers_strs(message) {
+        Ok(trailers) => trailers,
+        Err(_) => return credits,
+    };
+
+    for (key, value) in trailers.iter() {
+        let role = match key.trim().to_lowercase().as_str() {
+            "co-authored-by" => ContributionRole::CoAuthored,
+            "reviewed-by" => ContributionRole::Reviewed,
+            "signed-off-by" => ContributionRole::SignedOff,
+            _ => continue,
+        };
+
+        if let Some((name, email)) = parse_identity(value) {
+            credits.push((role, name, email));
+        }
+    }
+
+    credits
+}
+
+/// Parses an identity of the form `Name <email>`
+fn parse_identity(value: &str) -> Option<(String, String)> {
+    let value = value.trim();
+    let open = value.find('<')?;
+    let close = value[open..].find('>')? + open;
+
+    let name = value[..open].trim();
+    let email = value[open + 1..close].trim();
+    if email.is_empty() {
+        return None;
+    }
+
+    let name = if name.is_empty() { email } else { name };
+    Some((name.to_string(), email.to_string()))
+}
+
+fn merge_record(repo: &Repository, commit: &git2::Commit) -> Result<Option<MergeRecord>> {
+    if commit.parent_count() < 2 {
+        return Ok(None);
+    }
+
+    let mainline = commit.parent_id(0).context("Failed to get first parent")?;
+    let branch = commit.parent_id(1).context("Failed to get second parent")?;
+
+    // Unrelated histories have no merge base
+    let merge_base = match repo.merge_base(mainline, branch) {
+        Ok(merge_base) => repo
+            .find_commit(merge_base)
+            .context("Failed to find merge base")?,
+        Err(_) => return Ok(None),
+    };
+
+    let mut revwalk = repo.revwalk().context("Failed to create revwalk")?;
+    revwalk
+        .push(branch)
+        .context("Failed to push merged branch to revwalk")?;
+    revwalk
+        .hide(mainline)
+        .context("Failed to hide first parent from revwalk")?;
+
+    Ok(Some(MergeRecord {
+        merge_base_time: to_datetime(&merge_base.time()),
+        merge_time: to_datetime(&commit.time()),
+        branch_commits: revwalk.count(),
+    }))
+}
+
+/// Detects the kind of signature a commit carries, if any
+fn commit_signature(repo: &Repository, oid: git2::Oid) -> Option<SignatureKind> {
+    let (signature, _) = repo.extract_signature(&oid, None).ok()?;
+    let signature = String::from_utf8_lossy(&signature);
+
+    let kind = if signature.contains("BEGIN PGP SIGNATURE") {
+        SignatureKind::Gpg
+    } else if signature.contains("BEGIN SSH SIGNATURE") {
+        SignatureKind::Ssh
+    } else if signature.contains("BEGIN SIGNED MESSAGE") {
+        SignatureKind::X509
+    } else {
+        SignatureKind::Unknown
+    };
+    Some(kind)
+}
+
+/// Converts a git timestamp, keeping the UTC offset it was recorded with
+pub fn to_datetime(time: &Time) -> DateTime<FixedOffset> {
+    let offset = FixedOffset::east_opt(time.offset_minutes() * 60)
+        .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap());
+    offset
+        .timestamp_opt(time.seconds(), 0)
+        .single()
+        .unwrap_or_else(|| offset.timestamp_opt(0, 0).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    fn all_methods_auth() -> CloneAuth {
+        CloneAuth {
+            ssh_agent: true,
+            ssh_keys: vec![PathBuf::from("id_ed25519"), PathBuf::from("id_rsa")],
+            ssh_passphrase: None,
+            username: Some("alice".to_string()),
+            token: Some("token".to_string()),
+            credential_helper: true,
+        }
+    }
+
+    fn offered(
+        auth: &CloneAuth,
+        allowed: CredentialType,
+        tried: &mut Vec<AuthMethod>,
+    ) -> Vec<AuthMethod> {
+        std::iter::from_fn(|| next_auth_method(auth, allowed, tried)).collect()
+    }
+
+    #[test]
+    fn auth_methods_are_offered_once_in_order() {
+        let auth = all_methods_auth();
+        let mut tried = Vec::new();
+
+        assert_eq!(
+            offered(&auth, CredentialType::all(), &mut tried),
+            vec![
+                AuthMethod::SshAgent,
+                AuthMethod::SshKey(PathBuf::from("id_ed25519")),
+                AuthMethod::SshKey(PathBuf::from("id_rsa")),
+                AuthMethod::Token,
+                AuthMethod::CredentialHelper,
+                AuthMethod::Username,
+                AuthMethod::Default,
+            ]
+        );
+        assert_eq!(
+            next_auth_method(&auth, CredentialType::all(), &mut tried),
+            None
+        );
+    }
+
+    #[test]
+    fn auth_methods_follow_what_the_remote_allows() {
+        let auth = all_methods_auth();
+        let mut tried = Vec::new();
+
+        // An SSH server first asks for a username, then for keys
+        assert_eq!(
+            offered(&auth, CredentialType::USERNAME, &mut tried),
+            vec![AuthMethod::Username]
+        );
+        assert_eq!(
+            offered(
+                &auth,
+                CredentialType::SSH_KEY | CredentialType::USERNAME,
+                &mut tried
+            ),
+            vec![
+                AuthMethod::SshAgent,
+                AuthMethod::SshKey(PathBuf::from("id_ed25519")),
+                AuthMethod::SshKey(PathBuf::from("id_rsa")),
+            ]
+        );
+        assert_eq!(
+            next_auth_method(&auth, CredentialType::SSH_KEY, &mut tried),
+            None
+        );
+    }
+
+    fn path_filter(include: &[&str], exclude: &[&str]) -> PathFilter {
+        let globs = |globs: &[&str]| {
+            globs
+                .iter()
+                .map(|glob| glob.to_string())
+                .collect::<Vec<_>>()
+        };
+        PathFilter::new(&globs(include), &globs(exclude)).unwrap()
+    }
+
+    fn included(filter: &PathFilter, path: &str) -> bool {
+        filter.matches(Path::new(path))
+    }
+
+    #[test]
+    fn double_star_crosses_directories() {
+        let filter = path_filter(&["src/**/*.rs"], &[]);
+
+        assert!(included(&filter, "src/main.rs"));
+        assert!(included(&filter, "src/git/diff/mod.rs"));
+        assert!(!included(&filter, "lib/main.rs"));
+        assert!(!included(&filter, "src/main.rsx"));
+
+        let filter = path_filter(&["src/*.rs"], &[]);
+        assert!(included(&filter, "src/main.rs"));
+        assert!(!included(&filter, "src/git/mod.rs"));
+    }
+
+    #[test]
+    fn globs_without_a_slash_match_at_any_depth() {
+        let filter = path_filter(&[], &["*.md", "vendor"]);
+
+        assert!(!included(&filter, "README.md"));
+        assert!(!included(&filter, "docs/guide/intro.md"));
+        assert!(!included(&filter, "vendor/lib.rs"));
+        assert!(!included(&filter, "third_party/vendor/lib.rs"));
+        assert!(included(&filter, "vendored/lib.rs"));
+        assert!(included(&filter, "src/md.rs"));
+    }
+
+    #[test]
+    fn globs_with_a_slash_are_anchored_at_the_root() {
+        let filter = path_filter(&["./docs/api/"], &[]);
+
+        assert!(included(&filter, "docs/api"));
+        assert!(included(&filter, "docs/api/index.md"));
+        assert!(!included(&filter, "src/docs/api/index.md"));
+        assert!(!included(&filter, "docs/apis/index.md"));
+    }
+
+    #[test]
+    fn glob_classes_and_alternatives() {
+        let filter = path_filter(&["*.{rs,toml}", "data/file[0-9].csv", "[!_]*.py"], &[]);
+
+        assert!(included(&filter, "src/main.rs"));
+        assert!(included(&filter, "Cargo.toml"));
+        assert!(!included(&filter, "Cargo.lock"));
+        assert!(included(&filter, "data/file7.csv"));
+        assert!(!included(&filter, "data/fileA.csv"));
+        assert!(included(&filter, "tools/build.py"));
+        assert!(!included(&filter, "tools/_private.py"));
+    }
+
+    #[test]
+    fn excludes_win_over_includes() {
+        let filter = path_filter(&["src/**"], &["**/generated/**"]);
+
+        assert!(filter.is_active());
+        assert!(included(&filter, "src/lib.rs"));
+        assert!(!included(&filter, "src/generated/schema.rs"));
+        assert!(!included(&filter, "docs/index.md"));
+        assert!(!PathFilter::default().is_active());
+        assert!(included(&PathFilter::default(), "anything/at/all"));
+    }
+
+    #[test]
+    fn only_excluded_directories_are_skipped() {
+        let filter = path_filter(&["src/**"], &["target", "docs/build"]);
+
+        assert!(filter.excludes_dir(Path::new("target")));
+        assert!(filter.excludes_dir(Path::new("crates/cli/target")));
+        assert!(filter.excludes_dir(Path::new("docs/build")));
+        assert!(!filter.excludes_dir(Path::new("docs")));
+        // Not included, but a walk may still find included paths beneath it
+        assert!(!filter.excludes_dir(Path::new("lib")));
+        assert!(!filter.excludes_dir(Path::new("")));
+    }
+
+    /// A repository in a scratch directory, removed when dropped
+    struct ScratchRepo {
+        dir: PathBuf,
+        repo: Repository,
+    }
+
+    impl ScratchRepo {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "repo-analyzer-unit-{}-{}",
+                std::process::id(),
+                name
+            ));
+            std::fs::remove_dir_all(&dir).unwrap_or(());
+            let repo = Repository::init(&dir).unwrap();
+            ScratchRepo { dir, repo }
+        }
+
+        /// Commits a tree holding exactly `files`, without moving HEAD
+        fn commit(&self, files: &[(&str, &str)], parents: &[Oid]) -> Oid {
+            let mut index = self.repo.index().unwrap();
+            index.clear().unwrap();
+            for (path, content) in files {
+                let full_path = self.dir.join(path);
+                std::fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+                std::fs::write(&full_path, content).unwrap();
+                index.add_path(Path::new(path)).unwrap();
+            }
+            let tree = self.repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents: Vec<git2::Commit> = parents
+                .iter()
+                .map(|id| self.repo.find_commit(*id).unwrap())
+                .collect();
+            let parents: Vec<&git2::Commit> = parents.iter().collect();
+            let signature = git2::Signature::now("Alice", "alice@example.com").unwrap();
+            self.repo
+                .commit(None, &signature, &signature, "Commit", &tree, &parents)
+                .unwrap()
+        }
+
+        /// Diffs commits newest first, as the history walk does, returning
+        /// (path at HEAD, lines added, lines removed, deleted) for each
+        fn diff(
+            &self,
+            commits: &[Oid],
+            options: &HistoryOptions,
+        ) -> Vec<Vec<(PathBuf, usize, usize, bool)>> {
+            let mut renamed_to = HashMap::new();
+            commits
+                .iter()
+                .map(|id| {
+                    let commit = self.repo.find_commit(*id).unwrap();
+                    let mut changes: Vec<_> =
+                        diff_commit(&self.repo, &commit, options, &mut renamed_to)
+                            .unwrap()
+                            .into_iter()
+                            .map(|change| {
+                                (
+                                    change.path,
+                                    change.lines_added,
+                                    change.lines_removed,
+                                    change.deleted,
+                                )
+                            })
+                            .collect();
+                    changes.sort();
+                    changes
+                })
+                .collect()
+        }
+    }
+
+    impl Drop for ScratchRepo {
+        fn drop(&mut self) {
+            std::fs::remove_dir_all(&self.dir).unwrap_or(());
+        }
+    }
+
+    const TEN_LINES: &str = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\n";
+
+    #[test]
+    fn renamed_files_are_credited_to_their_path_at_head() {
+        let scratch = ScratchRepo::new("renames");
+        let added = scratch.commit(&[("src/old.rs", TEN_LINES)], &[]);
+        let moved = scratch.commit(
+            &[("src/new.rs", &format!("{}eleven\n", TEN_LINES))],
+            &[added],
+        );
+        let moved_again = scratch.commit(
+            &[("lib/final.rs", &format!("{}eleven\n", TEN_LINES))],
+            &[moved],
+        );
+
+        let final_path = PathBuf::from("lib/final.rs");
+        assert_eq!(
+            scratch.diff(&[moved_again, moved, added], &HistoryOptions::default()),
+            vec![
+                vec![(final_path.clone(), 0, 0, false)],
+                vec![(final_path.clone(), 1, 0, false)],
+                vec![(final_path, 10, 0, false)],
+            ]
+        );
+    }
+
+    #[test]
+    fn renames_are_not_followed_with_a_zero_threshold() {
+        let scratch = ScratchRepo::new("no-renames");
+        let added = scratch.commit(&[("old.rs", TEN_LINES)], &[]);
+        let moved = scratch.commit(&[("new.rs", TEN_LINES)], &[added]);
+
+        let options = HistoryOptions {
+            rename_threshold: 0,
+            ..HistoryOptions::default()
+        };
+        assert_eq!(
+            scratch.diff(&[moved, added], &options),
+            vec![
+                vec![
+                    (PathBuf::from("new.rs"), 10, 0, false),
+                    (PathBuf::from("old.rs"), 0, 10, true),
+                ],
+                vec![(PathBuf::from("old.rs"), 10, 0, false)],
+            ]
+        );
+    }
+
+    #[test]
+    fn root_commits_and_deletions_are_diffed() {
+        let scratch = ScratchRepo::new("deletions");
+        let root = scratch.commit(&[("a.txt", "1\n2\n3\n"), ("b.txt", "x\n")], &[]);
+        let deleted = scratch.commit(&[("a.txt", "1\n2\n3\n")], &[root]);
+
+        assert_eq!(
+            scratch.diff(&[deleted, root], &HistoryOptions::default()),
+            vec![
+                vec![(PathBuf::from("b.txt"), 0, 1, true)],
+                vec![
+                    (PathBuf::from("a.txt"), 3, 0, false),
+                    (PathBuf::from("b.txt"), 1, 0, false),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn merges_are_diffed_according_to_the_merge_strategy() {
+        let scratch = ScratchRepo::new("merges");
+        let base = scratch.commit(&[("a.txt", "1\n")], &[]);
+        let left = scratch.commit(&[("a.txt", "1\n2\n")], &[base]);
+        let right = scratch.commit(&[("a.txt", "1\n"), ("b.txt", "x\n")], &[base]);
+        // c.txt is only introduced by the merge itself
+        let merge = scratch.commit(
+            &[("a.txt", "1\n2\n"), ("b.txt", "x\n"), ("c.txt", "m\n")],
+            &[left, right],
+        );
+
+        let diff = |merge_strategy| {
+            let options = HistoryOptions {
+                merge_strategy,
+                ..HistoryOptions::default()
+            };
+            scratch.diff(&[merge], &options).remove(0)
+        };
+        assert_eq!(
+            diff(MergeStrategy::FirstParent),
+            vec![
+                (PathBuf::from("b.txt"), 1, 0, false),
+                (PathBuf::from("c.txt"), 1, 0, false),
+            ]
+        );
+        assert_eq!(
+            diff(MergeStrategy::Combined),
+            vec![(PathBuf::from("c.txt"), 1, 0, false)]
+        );
+        assert_eq!(diff(MergeStrategy::Skip), vec![]);
+    }
+
+    fn local_time(value: &str) -> Time {
+        let datetime = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap();
+        let datetime = Local.from_local_datetime(&datetime).earliest().unwrap();
+        Time::new(
+            datetime.timestamp(),
+            datetime.offset().local_minus_utc() / 60,
+        )
+    }
+
+    #[test]
+    fn until_date_includes_the_whole_day() {
+        let options = HistoryOptions {
+            since: Some(parse_date("2024-05-01").unwrap()),
+            until: Some(parse_until_date("2024-05-01").unwrap()),
+            ..HistoryOptions::default()
+        };
+
+        assert!(!in_date_window(
+            &local_time("2024-04-30 23:59:59"),
+            &options
+        ));
+        assert!(in_date_window(&local_time("2024-05-01 00:00:00"), &options));
+        assert!(in_date_window(&local_time("2024-05-01 23:59:59"), &options));
+        assert!(!in_date_window(
+            &local_time("2024-05-02 00:00:00"),
+            &options
+        ));
+    }
+
+    #[test]
+    fn until_time_is_exclusive() {
+        let options = HistoryOptions {
+            until: Some(parse_until_date("2024-05-01T12:00:00Z").unwrap()),
+            ..HistoryOptions::default()
+        };
+
+        assert!(in_date_window(&Time::new(1714564799, 0), &options));
+        assert!(!in_date_window(&Time::new(1714564800, 0), &options));
+    }
+
+    #[test]
+    fn disabled_auth_methods_are_not_offered() {
+        let auth = CloneAuth {
+            credential_helper: true,
+            ..CloneAuth::default()
+        };
+        let mut tried = Vec::new();
+
+        assert_eq!(
+            offered(
+                &auth,
+                CredentialType::SSH_KEY | CredentialType::USER_PASS_PLAINTEXT,
+                &mut tried
+            ),
+            vec![AuthMethod::CredentialHelper]
+        );
+    }
+
+    #[test]
+    fn trailers_credit_co_authors_reviewers_and_sign_offs() {
+        let message = "Fix parser\n\nLonger description.\n\n\
+            Co-authored-by: Bob <bob@example.com>\n\
+            Reviewed-by: Carol <carol@example.com>\n\
+            Signed-off-by: Alice <alice@example.com>\n\
+            Fixes: #12\n";
+
+        assert_eq!(
+            parse_commit_trailers(message),
+            vec![
+                (
+                    ContributionRole::CoAuthored,
+                    "Bob".to_string(),
+                    "bob@example.com".to_string()
+                ),
+                (
+                    ContributionRole::Reviewed,
+                    "Carol".to_string(),
+                    "carol@example.com".to_string()
+                ),
+                (
+                    ContributionRole::SignedOff,
+                    "Alice".to_string(),
+                    "alice@example.com".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_trailer_identities_are_skipped() {
+        let message = "Fix parser\n\n\
+            Co-authored-by: Bob bob@example.com\n\
+            Co-authored-by: Carol <>\n\
+            Co-authored-by: Dave <dave@example.com\n\
+            co-authored-by: <erin@example.com>\n";
+
+        // Without a name the email stands in for it
+        assert_eq!(
+            parse_commit_trailers(message),
+            vec![(
+                ContributionRole::CoAuthored,
+                "erin@example.com".to_string(),
+                "erin@example.com".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn trailers_outside_the_final_paragraph_are_ignored() {
+        let message = "Fix parser\n\nCo-authored-by: Bob <bob@example.com>\n\nMore text.\n";
+
+        assert!(parse_commit_trailers(message).is_empty());
+    }
 }
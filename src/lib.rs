@@ -1,5 +1,6 @@
 // Export modules
 pub mod analyzer;
+pub mod batch;
 pub mod cli;
 pub mod config;
 pub mod git;
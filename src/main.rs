@@ -1,35 +1,49 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
-use repo_analyzer::{analyzer, cli, report, s3};
-use std::path::Path;
-use tokio;
+use regex::Regex;
+use repo_analyzer::{analyzer, batch, cli, git, report, s3, Config};
+use std::path::{Path, PathBuf};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Parse command line arguments
     let cli = cli::Cli::parse();
 
-    // Determine repository path
+    let config = Config::load()?;
+    let clone_options = clone_options(&cli, &config);
+    let options = analysis_options(&cli, &config)?;
+
+    // Analyze every repository in the manifest and report on them together
+    if let Some(manifest) = &cli.batch {
+        return run_batch(&cli, manifest, clone_options, options);
+    }
+
+    // Determine repository path. A temporary clone is removed once `_clone`
+    // goes out of scope, whether or not the analysis succeeds
+    let mut _clone = None;
     let repo_path = if let Some(path) = &cli.repo_path {
         path.clone()
     } else if let Some(url) = &cli.remote_url {
-        // Clone remote repository to a temporary directory
         println!("Cloning repository from {}", url);
-        let temp_dir =
-            std::env::temp_dir().join(format!("repo-analyzer-{}", rand::random::<u32>()));
-        git2::Repository::clone(url, &temp_dir)?;
-        temp_dir
+        let clone = git::clone_remote(url, &clone_options)?;
+        let path = clone.path.clone();
+        _clone = Some(clone);
+        path
     } else {
         // This should not happen due to clap's required_unless_present
         return Err(anyhow::anyhow!("No repository path or URL provided"));
     };
 
     // Analyze repository
-    let analysis = analyzer::analyze_repository(&repo_path, cli.history_depth)?;
+    let analysis = analyzer::analyze_repository(&repo_path, &options)?;
 
     // Generate report
-    let report_files =
-        report::generate_report(&analysis, cli.output_format.clone(), cli.top_contributors)?;
+    let report_files = report::generate_report(
+        &analysis,
+        cli.output_format.clone(),
+        cli.top_contributors,
+        cli.time_zone,
+    )?;
 
     // Get the report file path based on the format
     let report_path = if let Some(custom_path) = &cli.output {
@@ -50,6 +64,10 @@ async fn main() -> Result<()> {
 
     println!("Report generated: {}", report_path);
 
+    if let Some(graph_path) = &cli.coupling_graph {
+        report::write_coupling_graph(&analysis, graph_path)?;
+    }
+
     // Upload report if requested
     if cli.upload {
         println!("Uploading report to cloud storage...");
@@ -66,5 +84,147 @@ async fn main() -> Result<()> {
         println!("Access your report at: {}", url);
     }
 
+    // Fail when the signing policy is not met
+    if let Some(range) = &cli.require_signed {
+        let unsigned = git::find_unsigned_commits(&repo_path, range)?;
+        if !unsigned.is_empty() {
+            println!("Unsigned commits in {}:", range);
+            for (id, author, subject) in &unsigned {
+                println!("{} {} ({})", &id[..7], subject, author);
+            }
+            return Err(anyhow::anyhow!(
+                "{} unsigned commits found in {}",
+                unsigned.len(),
+                range
+            ));
+        }
+        println!("All commits in {} are signed", range);
+    }
+
+    Ok(())
+}
+
+/// Build clone options from the command line, with credentials from the config
+/// file and environment
+fn clone_options(cli: &cli::Cli, config: &Config) -> git::CloneOptions {
+    git::CloneOptions {
+        depth: cli.clone_depth,
+        branch: cli.clone_branch.clone(),
+        cache_dir: cli.cache_dir.clone(),
+        auth: git::CloneAuth {
+            ssh_agent: config.auth.ssh_agent,
+            ssh_keys: config.auth.ssh_keys.clone(),
+            ssh_passphrase: config
+                .auth
+                .ssh_passphrase_env
+                .as_ref()
+                .and_then(|name| std::env::var(name).ok()),
+            username: config.auth.username.clone(),
+            token: std::env::var(&config.auth.token_env).ok(),
+            credential_helper: config.auth.credential_helper,
+        },
+    }
+}
+
+fn analysis_options(cli: &cli::Cli, config: &Config) -> Result<analyzer::AnalysisOptions> {
+    // Build history options from the command line and config file
+    let mut bot_patterns = git::default_bot_patterns();
+    for pattern in &config.bot_patterns {
+        bot_patterns.push(
+            Regex::new(pattern).with_context(|| format!("Invalid bot pattern: {}", pattern))?,
+        );
+    }
+
+    let mut issue_patterns = analyzer::default_issue_patterns();
+    for pattern in &config.issue_patterns {
+        issue_patterns.push(
+            Regex::new(pattern).with_context(|| format!("Invalid issue pattern: {}", pattern))?,
+        );
+    }
+
+    // Path globs from the config file apply in addition to the command line ones
+    let include: Vec<String> = cli.include.iter().chain(&config.include).cloned().collect();
+    let exclude: Vec<String> = cli.exclude.iter().chain(&config.exclude).cloned().collect();
+    let path_filter = git::PathFilter::new(&include, &exclude)?;
+
+    Ok(analyzer::AnalysisOptions {
+        history: git::HistoryOptions {
+            depth: cli.history_depth,
+            bot_patterns,
+            exclude_bots: cli.exclude_bots,
+            rename_threshold: cli.rename_threshold,
+            merge_strategy: cli.merge_strategy,
+            since: cli.since,
+            until: cli.until,
+            rev_range: cli.rev_range.clone(),
+            branches: cli.branches.clone(),
+            all_refs: cli.all,
+            first_parent: cli.first_parent,
+            path_filter,
+        },
+        blame: cli.blame,
+        inactive_months: cli.inactive_months,
+        issue_patterns,
+        stale_days: cli.stale_days,
+        recurse_submodules: cli.recurse_submodules,
+        exclude_submodules: cli.exclude_submodules,
+        components: cli.components,
+    })
+}
+
+fn run_batch(
+    cli: &cli::Cli,
+    manifest: &str,
+    clone_options: git::CloneOptions,
+    options: analyzer::AnalysisOptions,
+) -> Result<()> {
+    let sources = batch::read_manifest(manifest)?;
+    if sources.is_empty() {
+        return Err(anyhow::anyhow!("No repositories listed in {}", manifest));
+    }
+    println!(
+        "Analyzing {} repositories with {} jobs",
+        sources.len(),
+        cli.jobs
+    );
+
+    let batch_options = batch::BatchOptions {
+        jobs: cli.jobs,
+        output_dir: cli.batch_output.clone(),
+        format: cli.output_format.clone(),
+        top_contributors: cli.top_contributors,
+        time_zone: cli.time_zone,
+        clone: clone_options,
+        analysis: options,
+    };
+    let org = batch::analyze_batch(&sources, &batch_options)?;
+
+    let extension = match cli.output_format.to_lowercase().as_str() {
+        "html" => "html",
+        _ => "json",
+    };
+    let report_path = cli
+        .output
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| cli.batch_output.join(format!("org-report.{}", extension)));
+    if let Some(path) = report::generate_org_report(
+        &org,
+        &cli.output_format,
+        cli.top_contributors,
+        cli.time_zone,
+        report_path,
+    )? {
+        println!("Report generated: {}", path.display());
+    }
+
+    if !org.failures.is_empty() {
+        return Err(anyhow::anyhow!(
+            "{} of {} repositories failed to analyze",
+            org.failures.len(),
+            sources.len()
+        ));
+    }
+
     Ok(())
 }
@@ -1,12 +1,19 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset, Local, Utc};
 use colored::*;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
-use crate::analyzer::RepositoryAnalysis;
+use crate::analyzer::{
+    ActivityBucket, BranchHealth, CoupledPair, FileClass, Release, RepositoryAnalysis,
+    SizeDistribution,
+};
+use crate::batch::{OrgAnalysis, RepositorySummary};
+use crate::git::{self, Ownership};
 
 #[derive(Serialize)]
 struct JsonReport {
@@ -19,13 +26,73 @@ struct JsonReport {
     blank_lines: usize,
     commit_count: usize,
     contributors: Vec<ContributorInfo>,
-    last_activity: String,
+    bots: Vec<ContributorInfo>,
+    last_activity: Option<String>,
     file_extensions: Vec<ExtensionStat>,
     avg_file_size: f64,
     largest_files: Vec<LargeFileInfo>,
+    classified_files: Vec<ClassifiedFileInfo>,
     complexity_stats: ComplexityStats,
     file_age_stats: FileAgeStats,
     most_changed_files: Vec<FileChangeInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ownership: Option<OwnershipReport>,
+    bus_factor: BusFactorReport,
+    change_coupling: ChangeCouplingReport,
+    hotspots: Vec<HotspotInfo>,
+    activity: ActivityReport,
+    commit_messages: CommitMessageReport,
+    releases: ReleaseReport,
+    commit_sizes: CommitSizeReport,
+    blob_history: BlobHistoryReport,
+    signatures: SignatureReport,
+    branches: BranchHealthReport,
+    merges: MergeReport,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    components: Vec<ComponentReport>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    submodules: Vec<SubmoduleReport>,
+}
+
+#[derive(Serialize)]
+struct ComponentReport {
+    name: String,
+    path: String,
+    kind: String,
+    file_count: usize,
+    total_lines: usize,
+    language_lines: HashMap<String, usize>,
+    avg_complexity: f64,
+    max_complexity: usize,
+    commit_count: usize,
+    lines_added: usize,
+    lines_removed: usize,
+    contributors: Vec<ComponentContributorInfo>,
+    complex_files: Vec<ComplexFileInfo>,
+    changed_files: Vec<ComponentFileInfo>,
+}
+
+#[derive(Serialize)]
+struct ComponentContributorInfo {
+    name: String,
+    commit_count: usize,
+}
+
+#[derive(Serialize)]
+struct ComponentFileInfo {
+    path: String,
+    commit_count: usize,
+}
+
+#[derive(Serialize)]
+struct SubmoduleReport {
+    name: String,
+    path: String,
+    url: Option<String>,
+    pinned_commit: Option<String>,
+    initialized: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    report: Option<Box<JsonReport>>,
 }
 
 #[derive(Serialize)]
@@ -33,6 +100,7 @@ struct LanguageStat {
     language: String,
     count: usize,
     percentage: f64,
+    lines: usize,
 }
 
 #[derive(Serialize)]
@@ -47,6 +115,11 @@ struct ContributorInfo {
     name: String,
     email: String,
     commit_count: usize,
+    co_authored_count: usize,
+    reviewed_count: usize,
+    signed_off_count: usize,
+    committed_count: usize,
+    is_bot: bool,
     first_commit: String,
     last_commit: String,
 }
@@ -58,6 +131,15 @@ struct LargeFileInfo {
     size_human: String,
 }
 
+#[derive(Serialize)]
+struct ClassifiedFileInfo {
+    path: String,
+    class: String,
+    reason: String,
+    lines: usize,
+    size_bytes: usize,
+}
+
 #[derive(Serialize)]
 struct ComplexityStats {
     avg_complexity: f64,
@@ -112,36 +194,468 @@ struct FileChangeInfo {
     avg_changes_per_commit: f64,
 }
 
+#[derive(Serialize)]
+struct OwnershipReport {
+    directories: Vec<OwnershipInfo>,
+    files: Vec<OwnershipInfo>,
+}
+
+#[derive(Serialize)]
+struct OwnershipInfo {
+    path: String,
+    total_lines: usize,
+    primary_owner: Option<String>,
+    primary_owner_percentage: f64,
+    authors: Vec<AuthorShare>,
+}
+
+#[derive(Serialize)]
+struct AuthorShare {
+    author: String,
+    lines: usize,
+    percentage: f64,
+}
+
+#[derive(Serialize)]
+struct BusFactorReport {
+    inactive_months: u32,
+    repository: BusFactorInfo,
+    directories: Vec<BusFactorInfo>,
+    orphaned_files: Vec<OrphanedFileInfo>,
+}
+
+#[derive(Serialize)]
+struct BusFactorInfo {
+    path: String,
+    bus_factor: usize,
+    key_people: Vec<String>,
+    file_count: usize,
+}
+
+#[derive(Serialize)]
+struct OrphanedFileInfo {
+    path: String,
+    owners: Vec<String>,
+    last_active: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ChangeCouplingReport {
+    file_pairs: Vec<CoupledPairInfo>,
+    directory_pairs: Vec<CoupledPairInfo>,
+}
+
+#[derive(Serialize)]
+struct CoupledPairInfo {
+    first: String,
+    second: String,
+    co_changes: usize,
+    support: f64,
+    confidence_first_to_second: f64,
+    confidence_second_to_first: f64,
+}
+
+#[derive(Serialize)]
+struct HotspotInfo {
+    path: String,
+    score: f64,
+    complexity: usize,
+    commit_count: usize,
+    lines_changed: usize,
+    change_frequency: f64,
+}
+
+#[derive(Serialize)]
+struct ActivityReport {
+    daily: Vec<ActivityBucketInfo>,
+    weekly: Vec<ActivityBucketInfo>,
+    monthly: Vec<ActivityBucketInfo>,
+    punch_card: Vec<PunchCardRow>,
+}
+
+#[derive(Serialize)]
+struct ActivityBucketInfo {
+    period: String,
+    commits: usize,
+    lines_added: usize,
+    lines_removed: usize,
+}
+
+#[derive(Serialize)]
+struct CommitMessageReport {
+    commit_count: usize,
+    conventional_count: usize,
+    conventional_percentage: f64,
+    types: Vec<CommitTypeInfo>,
+    breaking_changes: usize,
+    issue_linked_count: usize,
+    issue_linked_percentage: f64,
+    top_references: Vec<IssueReferenceInfo>,
+    avg_subject_length: f64,
+    long_subjects: usize,
+    with_body: usize,
+    monthly: Vec<MessageBucketInfo>,
+}
+
+#[derive(Serialize)]
+struct CommitTypeInfo {
+    commit_type: String,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct IssueReferenceInfo {
+    reference: String,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct MessageBucketInfo {
+    period: String,
+    commits: usize,
+    features: usize,
+    fixes: usize,
+    feature_fix_ratio: Option<f64>,
+    issue_linked: usize,
+}
+
+#[derive(Serialize)]
+struct ReleaseReport {
+    releases: Vec<ReleaseInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unreleased: Option<ReleaseInfo>,
+    release_count: usize,
+    avg_days_between: f64,
+    median_days_between: f64,
+    shortest_days_between: f64,
+    longest_days_between: f64,
+    days_since_last_release: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct ReleaseInfo {
+    tag: String,
+    previous_tag: Option<String>,
+    date: String,
+    annotated: bool,
+    commit_count: usize,
+    contributors: Vec<ReleaseContributorInfo>,
+    files_changed: usize,
+    lines_added: usize,
+    lines_removed: usize,
+    types: Vec<CommitTypeInfo>,
+}
+
+#[derive(Serialize)]
+struct ReleaseContributorInfo {
+    name: String,
+    commits: usize,
+}
+
+#[derive(Serialize)]
+struct CommitSizeReport {
+    files_changed: SizeDistributionInfo,
+    lines_added: SizeDistributionInfo,
+    lines_removed: SizeDistributionInfo,
+    largest_commits: Vec<LargeCommitInfo>,
+}
+
+#[derive(Serialize)]
+struct SizeDistributionInfo {
+    median: usize,
+    p90: usize,
+    max: usize,
+}
+
+#[derive(Serialize)]
+struct LargeCommitInfo {
+    id: String,
+    author: String,
+    subject: String,
+    date: String,
+    files_changed: usize,
+    lines_added: usize,
+    lines_removed: usize,
+}
+
+#[derive(Serialize)]
+struct BlobHistoryReport {
+    blob_count: usize,
+    total_size: u64,
+    largest_blobs: Vec<HistoryBlobInfo>,
+    size_by_extension: Vec<ExtensionSizeInfo>,
+}
+
+#[derive(Serialize)]
+struct HistoryBlobInfo {
+    id: String,
+    path: String,
+    size: u64,
+    size_human: String,
+    commit_id: String,
+    is_binary: bool,
+    at_head: bool,
+}
+
+#[derive(Serialize)]
+struct ExtensionSizeInfo {
+    extension: String,
+    size: u64,
+    size_human: String,
+    blob_count: usize,
+}
+
+#[derive(Serialize)]
+struct SignatureReport {
+    commit_count: usize,
+    signed_count: usize,
+    signed_percentage: f64,
+    kinds: Vec<SignatureKindInfo>,
+    contributors: Vec<SignedContributorInfo>,
+    monthly: Vec<SignatureBucketInfo>,
+}
+
+#[derive(Serialize)]
+struct SignatureKindInfo {
+    kind: String,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct SignedContributorInfo {
+    name: String,
+    signed: usize,
+    commits: usize,
+    signed_percentage: f64,
+}
+
+#[derive(Serialize)]
+struct SignatureBucketInfo {
+    period: String,
+    commits: usize,
+    signed: usize,
+    signed_percentage: f64,
+}
+
+#[derive(Serialize)]
+struct BranchHealthReport {
+    default_branch: Option<String>,
+    stale_days: u32,
+    branches: Vec<BranchInfo>,
+    stale: Vec<String>,
+    merged: Vec<String>,
+    diverged: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct BranchInfo {
+    name: String,
+    remote: bool,
+    is_default: bool,
+    last_commit: String,
+    last_author: String,
+    ahead: usize,
+    behind: usize,
+    merged: bool,
+}
+
+#[derive(Serialize)]
+struct MergeReport {
+    merge_count: usize,
+    merges_per_week: f64,
+    avg_branch_lifetime_days: f64,
+    max_branch_lifetime_days: f64,
+    avg_commits_per_branch: f64,
+    weekly: Vec<WeeklyMergeInfo>,
+}
+
+#[derive(Serialize)]
+struct WeeklyMergeInfo {
+    week: String,
+    merges: usize,
+}
+
+#[derive(Serialize)]
+struct PunchCardRow {
+    weekday: String,
+    hours: Vec<usize>, // Commits per hour of the day, 0 to 23
+}
+
+#[derive(Serialize)]
+struct OrgJsonReport {
+    repository_count: usize,
+    failure_count: usize,
+    total_lines: usize,
+    code_lines: usize,
+    commit_count: usize,
+    language_lines: Vec<OrgLanguageInfo>,
+    repositories: Vec<OrgRepositoryInfo>,
+    contributors: Vec<OrgContributorInfo>,
+    failures: Vec<OrgFailureInfo>,
+}
+
+#[derive(Serialize)]
+struct OrgLanguageInfo {
+    language: String,
+    lines: usize,
+    percentage: f64,
+}
+
+#[derive(Serialize)]
+struct OrgRepositoryInfo {
+    source: String,
+    report: String,
+    file_count: usize,
+    total_lines: usize,
+    code_lines: usize,
+    commit_count: usize,
+    contributor_count: usize,
+    avg_complexity: f64,
+    total_complexity: usize,
+    last_activity: Option<String>,
+}
+
+#[derive(Serialize)]
+struct OrgContributorInfo {
+    name: String,
+    email: String,
+    repositories: usize,
+    commit_count: usize,
+}
+
+#[derive(Serialize)]
+struct OrgFailureInfo {
+    source: String,
+    error: String,
+}
+
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Time zone used to render dates in text and HTML reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayTimeZone {
+    /// The time zone of the machine running the analysis
+    Local,
+    Utc,
+    /// The offset each commit was recorded with
+    Original,
+    Fixed(FixedOffset),
+}
+
+impl DisplayTimeZone {
+    pub fn format(&self, datetime: &DateTime<FixedOffset>) -> String {
+        match self {
+            DisplayTimeZone::Local => datetime
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            DisplayTimeZone::Utc => datetime
+                .with_timezone(&Utc)
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string(),
+            DisplayTimeZone::Original => datetime.format("%Y-%m-%d %H:%M:%S %:z").to_string(),
+            DisplayTimeZone::Fixed(offset) => datetime
+                .with_timezone(offset)
+                .format("%Y-%m-%d %H:%M:%S %:z")
+                .to_string(),
+        }
+    }
+
+    fn format_opt(&self, datetime: Option<&DateTime<FixedOffset>>) -> String {
+        datetime
+            .map(|datetime| self.format(datetime))
+            .unwrap_or_else(|| "Unknown".to_string())
+    }
+}
+
+impl FromStr for DisplayTimeZone {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "local" => Ok(DisplayTimeZone::Local),
+            "utc" => Ok(DisplayTimeZone::Utc),
+            "original" => Ok(DisplayTimeZone::Original),
+            _ => DateTime::parse_from_str(&format!("2000-01-01 00:00 {}", s), "%Y-%m-%d %H:%M %:z")
+                .map(|datetime| DisplayTimeZone::Fixed(*datetime.offset()))
+                .map_err(|_| {
+                    format!(
+                        "Unknown time zone: {} (expected local, utc, original or an offset like +02:00)",
+                        s
+                    )
+                }),
+        }
+    }
+}
+
+/// Write the report of one repository to the given path, for callers that
+/// manage their own file names. Only the json and html formats are written
+pub fn generate_report_file(
+    analysis: &RepositoryAnalysis,
+    format: &str,
+    top_contributors: usize,
+    time_zone: DisplayTimeZone,
+    file_path: PathBuf,
+) -> Result<PathBuf> {
+    match format.to_lowercase().as_str() {
+        "json" => generate_json_report(analysis, top_contributors, file_path),
+        "html" => generate_html_report(analysis, top_contributors, time_zone, file_path),
+        _ => Err(anyhow::anyhow!(
+            "Unsupported report file format: {}",
+            format
+        )),
+    }
+}
+
 pub fn generate_report(
     analysis: &RepositoryAnalysis,
     format: String,
     top_contributors: usize,
+    time_zone: DisplayTimeZone,
 ) -> Result<HashMap<String, PathBuf>> {
     let mut report_files = HashMap::new();
 
     match format.to_lowercase().as_str() {
         "text" => {
-            generate_text_report(analysis, top_contributors)?;
+            generate_text_report(analysis, top_contributors, time_zone)?;
             // Text reports are printed to console, not saved to file
         }
         "json" => {
-            let file_path = generate_json_report(analysis, top_contributors)?;
+            let file_path =
+                generate_json_report(analysis, top_contributors, PathBuf::from("report.json"))?;
             report_files.insert("json".to_string(), file_path);
         }
         "html" => {
-            let file_path = generate_html_report(analysis, top_contributors)?;
+            let file_path = generate_html_report(
+                analysis,
+                top_contributors,
+                time_zone,
+                PathBuf::from("report.html"),
+            )?;
             report_files.insert("html".to_string(), file_path);
         }
         _ => {
             println!("Unsupported format: {}, defaulting to text", format);
-            generate_text_report(analysis, top_contributors)?;
+            generate_text_report(analysis, top_contributors, time_zone)?;
         }
     }
 
     Ok(report_files)
 }
 
-fn generate_text_report(analysis: &RepositoryAnalysis, top_contributors: usize) -> Result<()> {
+fn generate_text_report(
+    analysis: &RepositoryAnalysis,
+    top_contributors: usize,
+    time_zone: DisplayTimeZone,
+) -> Result<()> {
     println!("\n{}", "Repository Analysis Report".yellow().bold());
     println!("{}", "=========================".yellow());
 
@@ -150,7 +664,10 @@ fn generate_text_report(analysis: &RepositoryAnalysis, top_contributors: usize)
     println!("Total Files: {}", analysis.file_count);
     println!("Total Lines of Code: {}", analysis.total_lines);
     println!("Total Commits: {}", analysis.commit_count);
-    println!("Last Activity: {}", analysis.last_activity);
+    println!(
+        "Last Activity: {}",
+        time_zone.format_opt(analysis.last_activity.as_ref())
+    );
     println!(
         "Average File Size: {:.2} KB",
         analysis.avg_file_size / 1024.0
@@ -185,57 +702,552 @@ fn generate_text_report(analysis: &RepositoryAnalysis, top_contributors: usize)
         );
     }
 
+    if !analysis.classified_files.is_empty() {
+        println!(
+            "\n{}",
+            "Vendored, Generated and Minified Files:".cyan().bold()
+        );
+        for (class, count, lines) in classified_totals(analysis) {
+            println!("{}: {} files, {} lines", class.name(), count, lines);
+        }
+        for file in analysis.classified_files.iter().take(10) {
+            println!(
+                "- {} - {} ({}), {} lines",
+                relative_path(&analysis.repo_path, &file.path),
+                file.class.name(),
+                file.reason,
+                file.lines
+            );
+        }
+    }
+
     println!("\n{}", "Top Contributors:".cyan().bold());
     for (i, contributor) in analysis
         .contributors
         .iter()
+        .filter(|c| !c.is_bot)
         .enumerate()
         .take(top_contributors)
     {
         println!(
-            "{}. {} <{}> - {} commits (first: {}, last: {})",
+            "{}. {} <{}> - {} commits, {} co-authored, {} reviewed, {} committed (first: {}, last: {})",
             i + 1,
             contributor.name,
             contributor.email,
             contributor.commit_count,
-            contributor.first_commit,
-            contributor.last_commit
+            contributor.co_authored_count,
+            contributor.reviewed_count,
+            contributor.committed_count,
+            time_zone.format(&contributor.first_commit),
+            time_zone.format(&contributor.last_commit)
         );
     }
 
-    println!("\n{}", "Most Changed Files:".cyan().bold());
-    for (
-        i,
-        (
-            path,
-            commit_count,
-            lines_added,
-            lines_removed,
-            change_frequency,
-            top_contributor,
-            _,
-            _avg_changes,
-        ),
-    ) in analysis.most_changed_files.iter().enumerate().take(10)
-    {
+    let bots: Vec<_> = analysis.contributors.iter().filter(|c| c.is_bot).collect();
+    if !bots.is_empty() {
+        println!("\n{}", "Bot Accounts:".cyan().bold());
+        for bot in bots {
+            println!(
+                "- {} <{}> - {} commits, {} committed",
+                bot.name, bot.email, bot.commit_count, bot.committed_count
+            );
+        }
+    }
+
+    if let Some(ownership) = &analysis.ownership {
+        println!("\n{}", "Code Ownership by Directory:".cyan().bold());
+        for (path, owner) in sorted_by_lines(&ownership.directories).iter().take(10) {
+            if let Some((name, percentage)) = owner.primary_owner() {
+                println!(
+                    "- {} - {} lines, primary owner {} ({:.1}%)",
+                    path.display(),
+                    owner.total_lines,
+                    name,
+                    percentage
+                );
+            }
+        }
+
+        println!("\n{}", "Code Ownership by File:".cyan().bold());
+        for (path, owner) in sorted_by_lines(&ownership.files).iter().take(10) {
+            if let Some((name, percentage)) = owner.primary_owner() {
+                println!(
+                    "- {} - {} lines, primary owner {} ({:.1}%)",
+                    path.display(),
+                    owner.total_lines,
+                    name,
+                    percentage
+                );
+            }
+        }
+    }
+
+    let bus_factor = &analysis.bus_factor;
+    println!("\n{}", "Bus Factor:".cyan().bold());
+    println!(
+        "Repository: {} ({})",
+        bus_factor.repository.bus_factor,
+        bus_factor.repository.key_people.join(", ")
+    );
+    for (path, dir_factor) in &bus_factor.directories {
         println!(
-            "{}. {} - {} commits, +{} -{}, {:.2} changes/month, by {}",
-            i + 1,
+            "- {}: {} ({})",
             path.display(),
-            commit_count,
-            lines_added,
-            lines_removed,
-            change_frequency,
-            top_contributor
+            dir_factor.bus_factor,
+            dir_factor.key_people.join(", ")
         );
     }
 
-    Ok(())
-}
+    if !bus_factor.orphaned_files.is_empty() {
+        println!(
+            "\n{}",
+            format!(
+                "Files Owned Only by Authors Inactive for {} Months:",
+                bus_factor.inactive_months
+            )
+            .cyan()
+            .bold()
+        );
+        for orphan in bus_factor.orphaned_files.iter().take(10) {
+            println!(
+                "- {} - {} (last active: {})",
+                orphan.path.display(),
+                orphan.owners.join(", "),
+                time_zone.format_opt(orphan.last_active.as_ref())
+            );
+        }
+    }
+
+    for (title, pairs) in [
+        (
+            "Files That Change Together:",
+            &analysis.change_coupling.file_pairs,
+        ),
+        (
+            "Directories That Change Together:",
+            &analysis.change_coupling.directory_pairs,
+        ),
+    ] {
+        if pairs.is_empty() {
+            continue;
+        }
+        println!("\n{}", title.cyan().bold());
+        for pair in pairs.iter().take(10) {
+            println!(
+                "- {} <-> {} - {} shared commits, confidence {:.0}% / {:.0}%",
+                pair.first.display(),
+                pair.second.display(),
+                pair.co_changes,
+                pair.confidence_first_to_second * 100.0,
+                pair.confidence_second_to_first * 100.0
+            );
+        }
+    }
+
+    println!(
+        "\n{}",
+        "Hotspots (complex and frequently changed):".cyan().bold()
+    );
+    for (i, hotspot) in analysis.hotspots.iter().enumerate() {
+        println!(
+            "{}. {} - score {:.2}, complexity {}, {} commits, {} lines changed",
+            i + 1,
+            hotspot.path.display(),
+            hotspot.score,
+            hotspot.complexity,
+            hotspot.commit_count,
+            hotspot.lines_changed
+        );
+    }
+
+    println!("\n{}", "Monthly Activity:".cyan().bold());
+    println!(
+        "{:<10} {:>8} {:>10} {:>10}",
+        "Month", "Commits", "Added", "Removed"
+    );
+    for bucket in &analysis.activity.monthly {
+        println!(
+            "{:<10} {:>8} {:>10} {:>10}",
+            bucket.period, bucket.commits, bucket.lines_added, bucket.lines_removed
+        );
+    }
+
+    println!(
+        "\n{}",
+        "Punch Card (commits by weekday and hour, author time):"
+            .cyan()
+            .bold()
+    );
+    print!("{:<10}", "");
+    for hour in 0..24 {
+        print!("{:>3}", hour);
+    }
+    println!();
+    for (weekday, hours) in WEEKDAYS.iter().zip(analysis.activity.punch_card.iter()) {
+        print!("{:<10}", weekday);
+        for count in hours {
+            if *count == 0 {
+                print!("{:>3}", ".");
+            } else {
+                print!("{:>3}", count);
+            }
+        }
+        println!();
+    }
+
+    let messages = &analysis.commit_messages;
+    println!("\n{}", "Commit Messages:".cyan().bold());
+    println!(
+        "Conventional Commits: {} of {} ({:.1}%)",
+        messages.conventional_count,
+        messages.commit_count,
+        percentage(messages.conventional_count, messages.commit_count)
+    );
+    println!(
+        "Linked to an Issue: {} ({:.1}%)",
+        messages.issue_linked_count,
+        percentage(messages.issue_linked_count, messages.commit_count)
+    );
+    println!("Breaking Changes: {}", messages.breaking_changes);
+    println!(
+        "Average Subject Length: {:.1} characters ({} longer than 72)",
+        messages.avg_subject_length, messages.long_subjects
+    );
+    println!("Commits with a Body: {}", messages.with_body);
+    if !messages.types.is_empty() {
+        let types: Vec<String> = messages
+            .types
+            .iter()
+            .map(|(commit_type, count)| format!("{} {}", commit_type, count))
+            .collect();
+        println!("Types: {}", types.join(", "));
+    }
+    if !messages.top_references.is_empty() {
+        let references: Vec<String> = messages
+            .top_references
+            .iter()
+            .map(|(reference, count)| format!("{} ({})", reference, count))
+            .collect();
+        println!("Most Referenced Issues: {}", references.join(", "));
+    }
+    println!(
+        "{:<10} {:>8} {:>9} {:>6} {:>10} {:>8}",
+        "Month", "Commits", "Features", "Fixes", "Feat/Fix", "Linked"
+    );
+    for bucket in &messages.monthly {
+        println!(
+            "{:<10} {:>8} {:>9} {:>6} {:>10} {:>8}",
+            bucket.period,
+            bucket.commits,
+            bucket.features,
+            bucket.fixes,
+            format_ratio(bucket.feature_fix_ratio()),
+            bucket.issue_linked
+        );
+    }
+
+    let releases = &analysis.releases;
+    if !releases.releases.is_empty() {
+        println!("\n{}", "Releases:".cyan().bold());
+        println!(
+            "{:<16} {:<25} {:>8} {:>8} {:>6} {:>8} {:>8}  Types",
+            "Tag", "Date", "Commits", "Authors", "Files", "Added", "Removed"
+        );
+        for release in releases.releases.iter().chain(releases.unreleased.iter()) {
+            let types: Vec<String> = release
+                .types
+                .iter()
+                .map(|(commit_type, count)| format!("{} {}", commit_type, count))
+                .collect();
+            println!(
+                "{:<16} {:<25} {:>8} {:>8} {:>6} {:>8} {:>8}  {}",
+                release.tag,
+                time_zone.format(&release.date),
+                release.commit_count,
+                release.contributors.len(),
+                release.files_changed,
+                release.lines_added,
+                release.lines_removed,
+                types.join(", ")
+            );
+        }
+
+        let cadence = &releases.cadence;
+        println!(
+            "Release Cadence: {} releases, {:.1} days apart on average (median {:.1}, shortest {:.1}, longest {:.1})",
+            cadence.release_count,
+            cadence.avg_days_between,
+            cadence.median_days_between,
+            cadence.shortest_days_between,
+            cadence.longest_days_between
+        );
+        if let Some(days) = cadence.days_since_last_release {
+            println!("Days Since Last Release: {:.0}", days);
+        }
+    }
+
+    let sizes = &analysis.commit_sizes;
+    println!("\n{}", "Commit Sizes:".cyan().bold());
+    println!("{:<15} {:>8} {:>8} {:>8}", "", "Median", "P90", "Max");
+    for (label, distribution) in [
+        ("Files Changed", &sizes.files_changed),
+        ("Lines Added", &sizes.lines_added),
+        ("Lines Removed", &sizes.lines_removed),
+    ] {
+        println!(
+            "{:<15} {:>8} {:>8} {:>8}",
+            label, distribution.median, distribution.p90, distribution.max
+        );
+    }
+    println!("Largest Commits:");
+    for (i, commit) in sizes.largest_commits.iter().enumerate() {
+        println!(
+            "{}. {} {} - {} ({}, {} files, +{} -{})",
+            i + 1,
+            short_id(&commit.id),
+            commit.subject,
+            commit.author,
+            time_zone.format(&commit.date),
+            commit.files_changed,
+            commit.lines_added,
+            commit.lines_removed
+        );
+    }
+
+    let blob_history = &analysis.blob_history;
+    println!("\n{}", "Largest Blobs in History:".cyan().bold());
+    println!(
+        "{} blobs, {} in total",
+        blob_history.blob_count,
+        format_size(blob_history.total_size)
+    );
+    for (i, blob) in blob_history.largest_blobs.iter().enumerate().take(10) {
+        println!(
+            "{}. {} - {}, added in {}{}{}",
+            i + 1,
+            relative_path(&analysis.repo_path, &blob.path),
+            format_size(blob.size),
+            short_id(&blob.commit_id),
+            if blob.is_binary { ", binary" } else { "" },
+            if blob.at_head { "" } else { ", not at HEAD" }
+        );
+    }
+    println!("History Size by Extension:");
+    for (extension, size, count) in blob_history.size_by_extension.iter().take(10) {
+        println!(".{}: {} in {} blobs", extension, format_size(*size), count);
+    }
+
+    let signatures = &analysis.signatures;
+    println!("\n{}", "Commit Signatures:".cyan().bold());
+    println!(
+        "Signed Commits: {} of {} ({:.1}%)",
+        signatures.signed_count,
+        signatures.commit_count,
+        percentage(signatures.signed_count, signatures.commit_count)
+    );
+    if !signatures.kinds.is_empty() {
+        let kinds: Vec<String> = signatures
+            .kinds
+            .iter()
+            .map(|(kind, count)| format!("{} {}", kind, count))
+            .collect();
+        println!("Signature Types: {}", kinds.join(", "));
+    }
+    for (name, signed, total) in signatures.contributors.iter().take(top_contributors) {
+        println!(
+            "- {}: {} of {} signed ({:.1}%)",
+            name,
+            signed,
+            total,
+            percentage(*signed, *total)
+        );
+    }
+    println!(
+        "{:<10} {:>8} {:>8} {:>8}",
+        "Month", "Commits", "Signed", "Share"
+    );
+    for bucket in &signatures.monthly {
+        println!(
+            "{:<10} {:>8} {:>8} {:>7.1}%",
+            bucket.period,
+            bucket.commits,
+            bucket.signed,
+            percentage(bucket.signed, bucket.commits)
+        );
+    }
+
+    let branches = &analysis.branches;
+    println!("\n{}", "Branches:".cyan().bold());
+    println!(
+        "Default Branch: {}",
+        branches.default_branch.as_deref().unwrap_or("Unknown")
+    );
+    println!(
+        "{:<30} {:<25} {:<20} {:>6} {:>7}  Status",
+        "Branch", "Last Commit", "Author", "Ahead", "Behind"
+    );
+    for branch in &branches.branches {
+        println!(
+            "{:<30} {:<25} {:<20} {:>6} {:>7}  {}",
+            branch.name,
+            time_zone.format(&branch.last_commit),
+            branch.last_author,
+            branch.ahead,
+            branch.behind,
+            branch_status(branches, branch)
+        );
+    }
+    if !branches.stale.is_empty() {
+        println!(
+            "Stale (no commits for {} days): {}",
+            branches.stale_days,
+            branches.stale.join(", ")
+        );
+    }
+    if !branches.diverged.is_empty() {
+        println!("Long Diverged: {}", branches.diverged.join(", "));
+    }
+    if !branches.merged.is_empty() {
+        println!("Merged, Safe to Delete: {}", branches.merged.join(", "));
+    }
+
+    let merges = &analysis.merges;
+    println!("\n{}", "Merges:".cyan().bold());
+    println!("Merge Commits: {}", merges.merge_count);
+    println!("Merges per Week: {:.2}", merges.merges_per_week);
+    println!(
+        "Branch Lifetime: {:.1} days on average, {:.1} days at most (merge base to merge)",
+        merges.avg_branch_lifetime_days, merges.max_branch_lifetime_days
+    );
+    println!(
+        "Commits per Merged Branch: {:.1}",
+        merges.avg_commits_per_branch
+    );
+
+    println!("\n{}", "Most Changed Files:".cyan().bold());
+    for (
+        i,
+        (
+            path,
+            commit_count,
+            lines_added,
+            lines_removed,
+            change_frequency,
+            top_contributor,
+            _,
+            _avg_changes,
+        ),
+    ) in analysis.most_changed_files.iter().enumerate().take(10)
+    {
+        println!(
+            "{}. {} - {} commits, +{} -{}, {:.2} changes/month, by {}",
+            i + 1,
+            path.display(),
+            commit_count,
+            lines_added,
+            lines_removed,
+            change_frequency,
+            top_contributor
+        );
+    }
+
+    if !analysis.components.is_empty() {
+        println!("\n{}", "Components:".cyan().bold());
+        for component in &analysis.components {
+            println!(
+                "- {} ({}, {}) - {} files, {} lines, {} commits, +{} -{}, avg complexity {:.2}",
+                component.name,
+                component.kind.name(),
+                relative_path(&analysis.repo_path, &component.path),
+                component.file_count,
+                component.total_lines,
+                component.commit_count,
+                component.lines_added,
+                component.lines_removed,
+                component.avg_complexity
+            );
+            let languages: Vec<String> = sorted_language_lines(&component.language_lines)
+                .iter()
+                .take(3)
+                .map(|(language, lines)| {
+                    format!(
+                        "{} {:.1}%",
+                        language,
+                        percentage(*lines, component.total_lines)
+                    )
+                })
+                .collect();
+            let contributors: Vec<String> = component
+                .contributors
+                .iter()
+                .take(top_contributors)
+                .map(|(name, commits)| format!("{} ({})", name, commits))
+                .collect();
+            println!(
+                "  Languages: {}; Contributors: {}",
+                languages.join(", "),
+                contributors.join(", ")
+            );
+        }
+    }
+
+    if !analysis.submodules.is_empty() {
+        println!("\n{}", "Submodules:".cyan().bold());
+        for entry in &analysis.submodules {
+            let submodule = &entry.submodule;
+            println!(
+                "- {} ({}) - {} at {}{}",
+                submodule.name,
+                relative_path(&analysis.repo_path, &submodule.path),
+                submodule.url.as_deref().unwrap_or("no URL"),
+                submodule
+                    .pinned_commit
+                    .as_deref()
+                    .map(short_id)
+                    .unwrap_or("unknown commit"),
+                if submodule.initialized {
+                    ""
+                } else {
+                    ", not checked out"
+                }
+            );
+        }
+    }
 
-fn generate_json_report(analysis: &RepositoryAnalysis, top_contributors: usize) -> Result<PathBuf> {
+    // Nested reports for submodules analyzed recursively
+    for entry in &analysis.submodules {
+        if let Some(nested) = &entry.analysis {
+            println!(
+                "\n{}",
+                format!("Submodule {}", entry.submodule.name)
+                    .yellow()
+                    .bold()
+            );
+            generate_text_report(nested, top_contributors, time_zone)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn generate_json_report(
+    analysis: &RepositoryAnalysis,
+    top_contributors: usize,
+    file_path: PathBuf,
+) -> Result<PathBuf> {
     println!("Generating JSON report...");
 
+    let report = build_json_report(analysis, top_contributors);
+
+    // Write to file
+    let file = File::create(&file_path).context("Failed to create JSON report file")?;
+    serde_json::to_writer_pretty(file, &report).context("Failed to write JSON report")?;
+
+    println!("JSON report saved to {}", file_path.display());
+    Ok(file_path)
+}
+
+fn build_json_report(analysis: &RepositoryAnalysis, top_contributors: usize) -> JsonReport {
     // Convert language stats to serializable format
     let language_stats: Vec<LanguageStat> = analysis
         .language_stats
@@ -246,6 +1258,7 @@ fn generate_json_report(analysis: &RepositoryAnalysis, top_contributors: usize)
                 language: language.clone(),
                 count: *count,
                 percentage,
+                lines: analysis.language_lines.get(language).copied().unwrap_or(0),
             }
         })
         .collect();
@@ -264,18 +1277,33 @@ fn generate_json_report(analysis: &RepositoryAnalysis, top_contributors: usize)
         })
         .collect();
 
-    // Convert contributors to serializable format
+    // Convert contributors to serializable format, ranking people and bots separately
+    let to_contributor_info = |contributor: &crate::git::Contributor| ContributorInfo {
+        name: contributor.name.clone(),
+        email: contributor.email.clone(),
+        commit_count: contributor.commit_count,
+        co_authored_count: contributor.co_authored_count,
+        reviewed_count: contributor.reviewed_count,
+        signed_off_count: contributor.signed_off_count,
+        committed_count: contributor.committed_count,
+        is_bot: contributor.is_bot,
+        first_commit: contributor.first_commit.to_rfc3339(),
+        last_commit: contributor.last_commit.to_rfc3339(),
+    };
+
     let contributors: Vec<ContributorInfo> = analysis
         .contributors
         .iter()
+        .filter(|c| !c.is_bot)
         .take(top_contributors)
-        .map(|contributor| ContributorInfo {
-            name: contributor.name.clone(),
-            email: contributor.email.clone(),
-            commit_count: contributor.commit_count,
-            first_commit: contributor.first_commit.clone(),
-            last_commit: contributor.last_commit.clone(),
-        })
+        .map(to_contributor_info)
+        .collect();
+
+    let bots: Vec<ContributorInfo> = analysis
+        .contributors
+        .iter()
+        .filter(|c| c.is_bot)
+        .map(to_contributor_info)
         .collect();
 
     // Convert largest files to serializable format
@@ -318,7 +1346,7 @@ fn generate_json_report(analysis: &RepositoryAnalysis, top_contributors: usize)
         .iter()
         .map(|(path, date)| FileAgeInfo {
             path: path.display().to_string(),
-            date: date.clone(),
+            date: date.to_rfc3339(),
         })
         .collect();
 
@@ -328,7 +1356,7 @@ fn generate_json_report(analysis: &RepositoryAnalysis, top_contributors: usize)
         .iter()
         .map(|(path, date)| FileAgeInfo {
             path: path.display().to_string(),
-            date: date.clone(),
+            date: date.to_rfc3339(),
         })
         .collect();
 
@@ -364,7 +1392,7 @@ fn generate_json_report(analysis: &RepositoryAnalysis, top_contributors: usize)
                     lines_removed: *lines_removed,
                     change_frequency: *change_frequency,
                     top_contributor: top_contributor.clone(),
-                    last_modified: last_modified.clone(),
+                    last_modified: last_modified.to_rfc3339(),
                     avg_changes_per_commit: *avg_changes_per_commit,
                 }
             },
@@ -386,7 +1414,367 @@ fn generate_json_report(analysis: &RepositoryAnalysis, top_contributors: usize)
         most_modified_files,
     };
 
-    let report = JsonReport {
+    // Convert blame ownership, largest paths first
+    let to_ownership_info = |(path, owner): &(&PathBuf, &Ownership)| OwnershipInfo {
+        path: path.display().to_string(),
+        total_lines: owner.total_lines,
+        primary_owner: owner.primary_owner().map(|(name, _)| name.to_string()),
+        primary_owner_percentage: owner.primary_owner().map_or(0.0, |(_, pct)| pct),
+        authors: owner
+            .authors_by_lines()
+            .into_iter()
+            .map(|(author, lines, percentage)| AuthorShare {
+                author: author.to_string(),
+                lines,
+                percentage,
+            })
+            .collect(),
+    };
+
+    let ownership = analysis
+        .ownership
+        .as_ref()
+        .map(|ownership| OwnershipReport {
+            directories: sorted_by_lines(&ownership.directories)
+                .iter()
+                .map(to_ownership_info)
+                .collect(),
+            files: sorted_by_lines(&ownership.files)
+                .iter()
+                .map(to_ownership_info)
+                .collect(),
+        });
+
+    // Convert bus factor stats
+    let to_bus_factor_info =
+        |path: &std::path::Path, factor: &crate::analyzer::BusFactor| BusFactorInfo {
+            path: path.display().to_string(),
+            bus_factor: factor.bus_factor,
+            key_people: factor.key_people.clone(),
+            file_count: factor.file_count,
+        };
+
+    let bus_factor = BusFactorReport {
+        inactive_months: analysis.bus_factor.inactive_months,
+        repository: to_bus_factor_info(&analysis.repo_path, &analysis.bus_factor.repository),
+        directories: analysis
+            .bus_factor
+            .directories
+            .iter()
+            .map(|(path, factor)| to_bus_factor_info(path, factor))
+            .collect(),
+        orphaned_files: analysis
+            .bus_factor
+            .orphaned_files
+            .iter()
+            .map(|orphan| OrphanedFileInfo {
+                path: orphan.path.display().to_string(),
+                owners: orphan.owners.clone(),
+                last_active: orphan.last_active.map(|last| last.to_rfc3339()),
+            })
+            .collect(),
+    };
+
+    // Convert change coupling
+    let to_coupled_pair_info = |pair: &CoupledPair| CoupledPairInfo {
+        first: pair.first.display().to_string(),
+        second: pair.second.display().to_string(),
+        co_changes: pair.co_changes,
+        support: pair.support,
+        confidence_first_to_second: pair.confidence_first_to_second,
+        confidence_second_to_first: pair.confidence_second_to_first,
+    };
+
+    let change_coupling = ChangeCouplingReport {
+        file_pairs: analysis
+            .change_coupling
+            .file_pairs
+            .iter()
+            .map(to_coupled_pair_info)
+            .collect(),
+        directory_pairs: analysis
+            .change_coupling
+            .directory_pairs
+            .iter()
+            .map(to_coupled_pair_info)
+            .collect(),
+    };
+
+    // Convert hotspots
+    let hotspots: Vec<HotspotInfo> = analysis
+        .hotspots
+        .iter()
+        .map(|hotspot| HotspotInfo {
+            path: hotspot.path.display().to_string(),
+            score: hotspot.score,
+            complexity: hotspot.complexity,
+            commit_count: hotspot.commit_count,
+            lines_changed: hotspot.lines_changed,
+            change_frequency: hotspot.change_frequency,
+        })
+        .collect();
+
+    // Convert activity
+    let to_bucket_info = |bucket: &ActivityBucket| ActivityBucketInfo {
+        period: bucket.period.clone(),
+        commits: bucket.commits,
+        lines_added: bucket.lines_added,
+        lines_removed: bucket.lines_removed,
+    };
+
+    let activity = ActivityReport {
+        daily: analysis.activity.daily.iter().map(to_bucket_info).collect(),
+        weekly: analysis
+            .activity
+            .weekly
+            .iter()
+            .map(to_bucket_info)
+            .collect(),
+        monthly: analysis
+            .activity
+            .monthly
+            .iter()
+            .map(to_bucket_info)
+            .collect(),
+        punch_card: WEEKDAYS
+            .iter()
+            .zip(analysis.activity.punch_card.iter())
+            .map(|(weekday, hours)| PunchCardRow {
+                weekday: weekday.to_string(),
+                hours: hours.to_vec(),
+            })
+            .collect(),
+    };
+
+    // Convert commit message stats
+    let messages = &analysis.commit_messages;
+    let commit_messages = CommitMessageReport {
+        commit_count: messages.commit_count,
+        conventional_count: messages.conventional_count,
+        conventional_percentage: percentage(messages.conventional_count, messages.commit_count),
+        types: messages
+            .types
+            .iter()
+            .map(|(commit_type, count)| CommitTypeInfo {
+                commit_type: commit_type.clone(),
+                count: *count,
+            })
+            .collect(),
+        breaking_changes: messages.breaking_changes,
+        issue_linked_count: messages.issue_linked_count,
+        issue_linked_percentage: percentage(messages.issue_linked_count, messages.commit_count),
+        top_references: messages
+            .top_references
+            .iter()
+            .map(|(reference, count)| IssueReferenceInfo {
+                reference: reference.clone(),
+                count: *count,
+            })
+            .collect(),
+        avg_subject_length: messages.avg_subject_length,
+        long_subjects: messages.long_subjects,
+        with_body: messages.with_body,
+        monthly: messages
+            .monthly
+            .iter()
+            .map(|bucket| MessageBucketInfo {
+                period: bucket.period.clone(),
+                commits: bucket.commits,
+                features: bucket.features,
+                fixes: bucket.fixes,
+                feature_fix_ratio: bucket.feature_fix_ratio(),
+                issue_linked: bucket.issue_linked,
+            })
+            .collect(),
+    };
+
+    // Convert releases
+    let to_release_info = |release: &Release| ReleaseInfo {
+        tag: release.tag.clone(),
+        previous_tag: release.previous_tag.clone(),
+        date: release.date.to_rfc3339(),
+        annotated: release.annotated,
+        commit_count: release.commit_count,
+        contributors: release
+            .contributors
+            .iter()
+            .map(|(name, commits)| ReleaseContributorInfo {
+                name: name.clone(),
+                commits: *commits,
+            })
+            .collect(),
+        files_changed: release.files_changed,
+        lines_added: release.lines_added,
+        lines_removed: release.lines_removed,
+        types: release
+            .types
+            .iter()
+            .map(|(commit_type, count)| CommitTypeInfo {
+                commit_type: commit_type.clone(),
+                count: *count,
+            })
+            .collect(),
+    };
+
+    let cadence = &analysis.releases.cadence;
+    let releases = ReleaseReport {
+        releases: analysis
+            .releases
+            .releases
+            .iter()
+            .map(to_release_info)
+            .collect(),
+        unreleased: analysis.releases.unreleased.as_ref().map(to_release_info),
+        release_count: cadence.release_count,
+        avg_days_between: cadence.avg_days_between,
+        median_days_between: cadence.median_days_between,
+        shortest_days_between: cadence.shortest_days_between,
+        longest_days_between: cadence.longest_days_between,
+        days_since_last_release: cadence.days_since_last_release,
+    };
+
+    // Convert commit sizes
+    let to_distribution_info = |distribution: &SizeDistribution| SizeDistributionInfo {
+        median: distribution.median,
+        p90: distribution.p90,
+        max: distribution.max,
+    };
+
+    let sizes = &analysis.commit_sizes;
+    let commit_sizes = CommitSizeReport {
+        files_changed: to_distribution_info(&sizes.files_changed),
+        lines_added: to_distribution_info(&sizes.lines_added),
+        lines_removed: to_distribution_info(&sizes.lines_removed),
+        largest_commits: sizes
+            .largest_commits
+            .iter()
+            .map(|commit| LargeCommitInfo {
+                id: commit.id.clone(),
+                author: commit.author.clone(),
+                subject: commit.subject.clone(),
+                date: commit.date.to_rfc3339(),
+                files_changed: commit.files_changed,
+                lines_added: commit.lines_added,
+                lines_removed: commit.lines_removed,
+            })
+            .collect(),
+    };
+
+    // Convert blob history
+    let blob_history = BlobHistoryReport {
+        blob_count: analysis.blob_history.blob_count,
+        total_size: analysis.blob_history.total_size,
+        largest_blobs: analysis
+            .blob_history
+            .largest_blobs
+            .iter()
+            .map(|blob| HistoryBlobInfo {
+                id: blob.id.clone(),
+                path: blob.path.display().to_string(),
+                size: blob.size,
+                size_human: format_size(blob.size),
+                commit_id: blob.commit_id.clone(),
+                is_binary: blob.is_binary,
+                at_head: blob.at_head,
+            })
+            .collect(),
+        size_by_extension: analysis
+            .blob_history
+            .size_by_extension
+            .iter()
+            .map(|(extension, size, count)| ExtensionSizeInfo {
+                extension: extension.clone(),
+                size: *size,
+                size_human: format_size(*size),
+                blob_count: *count,
+            })
+            .collect(),
+    };
+
+    // Convert signature stats
+    let signatures = SignatureReport {
+        commit_count: analysis.signatures.commit_count,
+        signed_count: analysis.signatures.signed_count,
+        signed_percentage: percentage(
+            analysis.signatures.signed_count,
+            analysis.signatures.commit_count,
+        ),
+        kinds: analysis
+            .signatures
+            .kinds
+            .iter()
+            .map(|(kind, count)| SignatureKindInfo {
+                kind: kind.clone(),
+                count: *count,
+            })
+            .collect(),
+        contributors: analysis
+            .signatures
+            .contributors
+            .iter()
+            .map(|(name, signed, total)| SignedContributorInfo {
+                name: name.clone(),
+                signed: *signed,
+                commits: *total,
+                signed_percentage: percentage(*signed, *total),
+            })
+            .collect(),
+        monthly: analysis
+            .signatures
+            .monthly
+            .iter()
+            .map(|bucket| SignatureBucketInfo {
+                period: bucket.period.clone(),
+                commits: bucket.commits,
+                signed: bucket.signed,
+                signed_percentage: percentage(bucket.signed, bucket.commits),
+            })
+            .collect(),
+    };
+
+    // Convert branch health
+    let branches = BranchHealthReport {
+        default_branch: analysis.branches.default_branch.clone(),
+        stale_days: analysis.branches.stale_days,
+        branches: analysis
+            .branches
+            .branches
+            .iter()
+            .map(|branch| BranchInfo {
+                name: branch.name.clone(),
+                remote: branch.remote,
+                is_default: branch.is_default,
+                last_commit: branch.last_commit.to_rfc3339(),
+                last_author: branch.last_author.clone(),
+                ahead: branch.ahead,
+                behind: branch.behind,
+                merged: branch.merged,
+            })
+            .collect(),
+        stale: analysis.branches.stale.clone(),
+        merged: analysis.branches.merged.clone(),
+        diverged: analysis.branches.diverged.clone(),
+    };
+
+    // Convert merge stats
+    let merges = MergeReport {
+        merge_count: analysis.merges.merge_count,
+        merges_per_week: analysis.merges.merges_per_week,
+        avg_branch_lifetime_days: analysis.merges.avg_branch_lifetime_days,
+        max_branch_lifetime_days: analysis.merges.max_branch_lifetime_days,
+        avg_commits_per_branch: analysis.merges.avg_commits_per_branch,
+        weekly: analysis
+            .merges
+            .weekly
+            .iter()
+            .map(|(week, merges)| WeeklyMergeInfo {
+                week: week.clone(),
+                merges: *merges,
+            })
+            .collect(),
+    };
+
+    JsonReport {
         repo_path: analysis.repo_path.display().to_string(),
         file_count: analysis.file_count,
         language_stats,
@@ -396,55 +1784,107 @@ fn generate_json_report(analysis: &RepositoryAnalysis, top_contributors: usize)
         blank_lines: analysis.blank_lines,
         commit_count: analysis.commit_count,
         contributors,
-        last_activity: analysis.last_activity.clone(),
+        bots,
+        last_activity: analysis.last_activity.map(|last| last.to_rfc3339()),
         file_extensions,
         avg_file_size: analysis.avg_file_size,
         largest_files,
+        classified_files: analysis
+            .classified_files
+            .iter()
+            .map(|file| ClassifiedFileInfo {
+                path: file.path.display().to_string(),
+                class: file.class.name().to_string(),
+                reason: file.reason.clone(),
+                lines: file.lines,
+                size_bytes: file.size_bytes,
+            })
+            .collect(),
         complexity_stats,
         file_age_stats,
         most_changed_files,
-    };
-
-    // Write to file
-    let file_path = PathBuf::from("report.json");
-    let file = File::create(&file_path).context("Failed to create JSON report file")?;
-    serde_json::to_writer_pretty(file, &report).context("Failed to write JSON report")?;
-
-    println!("JSON report saved to {}", file_path.display());
-    Ok(file_path)
+        ownership,
+        bus_factor,
+        change_coupling,
+        hotspots,
+        activity,
+        commit_messages,
+        releases,
+        commit_sizes,
+        blob_history,
+        signatures,
+        branches,
+        merges,
+        components: analysis
+            .components
+            .iter()
+            .map(|component| ComponentReport {
+                name: component.name.clone(),
+                path: relative_path(&analysis.repo_path, &component.path),
+                kind: component.kind.name().to_string(),
+                file_count: component.file_count,
+                total_lines: component.total_lines,
+                language_lines: component.language_lines.clone(),
+                avg_complexity: component.avg_complexity,
+                max_complexity: component.max_complexity,
+                commit_count: component.commit_count,
+                lines_added: component.lines_added,
+                lines_removed: component.lines_removed,
+                contributors: component
+                    .contributors
+                    .iter()
+                    .take(top_contributors)
+                    .map(|(name, commit_count)| ComponentContributorInfo {
+                        name: name.clone(),
+                        commit_count: *commit_count,
+                    })
+                    .collect(),
+                complex_files: component
+                    .complex_files
+                    .iter()
+                    .map(|(path, complexity)| ComplexFileInfo {
+                        path: path.display().to_string(),
+                        complexity: *complexity,
+                    })
+                    .collect(),
+                changed_files: component
+                    .changed_files
+                    .iter()
+                    .map(|(path, commit_count)| ComponentFileInfo {
+                        path: path.display().to_string(),
+                        commit_count: *commit_count,
+                    })
+                    .collect(),
+            })
+            .collect(),
+        submodules: analysis
+            .submodules
+            .iter()
+            .map(|entry| SubmoduleReport {
+                name: entry.submodule.name.clone(),
+                path: entry.submodule.path.display().to_string(),
+                url: entry.submodule.url.clone(),
+                pinned_commit: entry.submodule.pinned_commit.clone(),
+                initialized: entry.submodule.initialized,
+                report: entry
+                    .analysis
+                    .as_ref()
+                    .map(|nested| Box::new(build_json_report(nested, top_contributors))),
+            })
+            .collect(),
+    }
 }
 
-fn generate_html_report(analysis: &RepositoryAnalysis, top_contributors: usize) -> Result<PathBuf> {
+fn generate_html_report(
+    analysis: &RepositoryAnalysis,
+    top_contributors: usize,
+    time_zone: DisplayTimeZone,
+    file_path: PathBuf,
+) -> Result<PathBuf> {
     println!("Generating HTML report...");
 
     let mut html = String::new();
-    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
-    html.push_str("<meta charset=\"UTF-8\">\n");
-    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n");
-    html.push_str("<title>Repository Analysis Report</title>\n");
-    html.push_str("<style>\n");
-    html.push_str("body { font-family: Arial, sans-serif; line-height: 1.6; color: #333; max-width: 1200px; margin: 0 auto; padding: 20px; }\n");
-    html.push_str("h1, h2, h3 { color: #2c3e50; }\n");
-    html.push_str("table { border-collapse: collapse; width: 100%; margin-bottom: 20px; }\n");
-    html.push_str("th, td { text-align: left; padding: 12px; border-bottom: 1px solid #ddd; }\n");
-    html.push_str("th { background-color: #f2f2f2; }\n");
-    html.push_str("tr:hover { background-color: #f5f5f5; }\n");
-    html.push_str(".card { background: white; border-radius: 5px; box-shadow: 0 2px 5px rgba(0,0,0,0.1); padding: 20px; margin-bottom: 20px; }\n");
-    html.push_str(".stat { font-size: 24px; font-weight: bold; color: #3498db; }\n");
-    html.push_str(".stat-label { font-size: 14px; color: #7f8c8d; }\n");
-    html.push_str(
-        ".stats-container { display: flex; flex-wrap: wrap; gap: 20px; margin-bottom: 20px; }\n",
-    );
-    html.push_str(".stat-box { flex: 1; min-width: 150px; background: #f8f9fa; padding: 15px; border-radius: 5px; text-align: center; }\n");
-    html.push_str(".progress-bar { height: 10px; background: #ecf0f1; border-radius: 5px; margin-top: 5px; overflow: hidden; }\n");
-    html.push_str(".progress-fill { height: 100%; background: #3498db; }\n");
-    html.push_str(".tabs { display: flex; margin-bottom: 20px; }\n");
-    html.push_str(".tab { padding: 10px 20px; cursor: pointer; background: #f2f2f2; border-radius: 5px 5px 0 0; }\n");
-    html.push_str(".tab.active { background: #3498db; color: white; }\n");
-    html.push_str(".tab-content { display: none; }\n");
-    html.push_str(".tab-content.active { display: block; }\n");
-    html.push_str("</style>\n");
-    html.push_str("</head>\n<body>\n");
+    push_html_head(&mut html, "Repository Analysis Report");
 
     // Header
     html.push_str(&format!(
@@ -467,7 +1907,7 @@ fn generate_html_report(analysis: &RepositoryAnalysis, top_contributors: usize)
         analysis.commit_count));
 
     html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{}</div><div class=\"stat-label\">Contributors</div></div>\n", 
-        analysis.contributors.len()));
+        analysis.contributors.iter().filter(|c| !c.is_bot).count()));
 
     html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{:.2}</div><div class=\"stat-label\">Avg Complexity</div></div>\n", 
         analysis.complexity_stats.avg_complexity));
@@ -495,24 +1935,84 @@ fn generate_html_report(analysis: &RepositoryAnalysis, top_contributors: usize)
     html.push_str("</table>\n");
     html.push_str("</div>\n"); // End card
 
+    // Files kept out of the code metrics
+    if !analysis.classified_files.is_empty() {
+        html.push_str("<div class=\"card\">\n");
+        html.push_str("<h2>Vendored, Generated and Minified Files</h2>\n");
+        html.push_str(
+            "<p>These files are excluded from line counts, complexity, duplicates and churn.</p>\n",
+        );
+        html.push_str("<div class=\"stats-container\">\n");
+        for (class, count, lines) in classified_totals(analysis) {
+            html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{}</div><div class=\"stat-label\">{} files, {} lines</div></div>\n",
+                count, class.name(), lines));
+        }
+        html.push_str("</div>\n"); // End stats-container
+
+        html.push_str("<table>\n");
+        html.push_str(
+            "<tr><th>File</th><th>Class</th><th>Reason</th><th>Lines</th><th>Size</th></tr>\n",
+        );
+        for file in analysis.classified_files.iter().take(20) {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                relative_path(&analysis.repo_path, &file.path),
+                file.class.name(),
+                file.reason,
+                file.lines,
+                format_size(file.size_bytes as u64)
+            ));
+        }
+        html.push_str("</table>\n");
+        html.push_str("</div>\n"); // End card
+    }
+
     // Contributors
     html.push_str("<div class=\"card\">\n");
     html.push_str("<h2>Top Contributors</h2>\n");
     html.push_str("<table>\n");
-    html.push_str("<tr><th>Name</th><th>Email</th><th>Commits</th><th>First Commit</th><th>Last Commit</th></tr>\n");
+    html.push_str("<tr><th>Name</th><th>Email</th><th>Commits</th><th>Co-authored</th><th>Reviewed</th><th>Committed</th><th>First Commit</th><th>Last Commit</th></tr>\n");
 
-    for contributor in analysis.contributors.iter().take(top_contributors) {
+    for contributor in analysis
+        .contributors
+        .iter()
+        .filter(|c| !c.is_bot)
+        .take(top_contributors)
+    {
         html.push_str(&format!(
-            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
-            contributor.name,
-            contributor.email,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            html_escape(&contributor.name),
+            html_escape(&contributor.email),
             contributor.commit_count,
-            contributor.first_commit,
-            contributor.last_commit
+            contributor.co_authored_count,
+            contributor.reviewed_count,
+            contributor.committed_count,
+            time_zone.format(&contributor.first_commit),
+            time_zone.format(&contributor.last_commit)
         ));
     }
 
     html.push_str("</table>\n");
+
+    let bots: Vec<_> = analysis.contributors.iter().filter(|c| c.is_bot).collect();
+    if !bots.is_empty() {
+        html.push_str("<h3>Bot Accounts</h3>\n");
+        html.push_str("<table>\n");
+        html.push_str("<tr><th>Name</th><th>Email</th><th>Commits</th><th>Committed</th></tr>\n");
+
+        for bot in bots {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                html_escape(&bot.name),
+                html_escape(&bot.email),
+                bot.commit_count,
+                bot.committed_count
+            ));
+        }
+
+        html.push_str("</table>\n");
+    }
+
     html.push_str("</div>\n"); // End card
 
     // Code Complexity
@@ -561,7 +2061,7 @@ fn generate_html_report(analysis: &RepositoryAnalysis, top_contributors: usize)
         html.push_str(&format!(
             "<tr><td>{}</td><td>{}</td></tr>\n",
             path.display(),
-            date
+            time_zone.format(date)
         ));
     }
 
@@ -575,7 +2075,7 @@ fn generate_html_report(analysis: &RepositoryAnalysis, top_contributors: usize)
         html.push_str(&format!(
             "<tr><td>{}</td><td>{}</td></tr>\n",
             path.display(),
-            date
+            time_zone.format(date)
         ));
     }
 
@@ -620,13 +2120,631 @@ fn generate_html_report(analysis: &RepositoryAnalysis, top_contributors: usize)
             lines_added,
             lines_removed,
             change_frequency,
-            top_contributor
+            html_escape(top_contributor)
+        ));
+    }
+
+    html.push_str("</table>\n");
+    html.push_str("</div>\n"); // End card
+
+    // Activity
+    let monthly = &analysis.activity.monthly;
+    let labels: Vec<String> = monthly.iter().map(|b| b.period.clone()).collect();
+    html.push_str("<div class=\"card\">\n");
+    html.push_str("<h2>Commit Activity</h2>\n");
+    html.push_str("<h3>Commits per Month</h3>\n");
+    html.push_str(&svg_bar_chart(
+        &labels,
+        &[(
+            "Commits",
+            "#3498db",
+            monthly.iter().map(|b| b.commits).collect(),
+        )],
+    ));
+    html.push_str("<h3>Lines Changed per Month</h3>\n");
+    html.push_str(&svg_bar_chart(
+        &labels,
+        &[
+            (
+                "Added",
+                "#27ae60",
+                monthly.iter().map(|b| b.lines_added).collect(),
+            ),
+            (
+                "Removed",
+                "#e74c3c",
+                monthly.iter().map(|b| b.lines_removed).collect(),
+            ),
+        ],
+    ));
+    html.push_str("<h3>Punch Card (author time)</h3>\n");
+    html.push_str(&svg_punch_card(&analysis.activity.punch_card));
+    html.push_str("</div>\n"); // End card
+
+    // Commit messages
+    let messages = &analysis.commit_messages;
+    html.push_str("<div class=\"card\">\n");
+    html.push_str("<h2>Commit Messages</h2>\n");
+    html.push_str("<div class=\"stats-container\">\n");
+    html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{:.1}%</div><div class=\"stat-label\">Conventional Commits</div></div>\n",
+        percentage(messages.conventional_count, messages.commit_count)));
+    html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{:.1}%</div><div class=\"stat-label\">Linked to an Issue</div></div>\n",
+        percentage(messages.issue_linked_count, messages.commit_count)));
+    html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{}</div><div class=\"stat-label\">Breaking Changes</div></div>\n",
+        messages.breaking_changes));
+    html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{:.1}</div><div class=\"stat-label\">Avg Subject Length</div></div>\n",
+        messages.avg_subject_length));
+    html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{}</div><div class=\"stat-label\">Commits with a Body</div></div>\n",
+        messages.with_body));
+    html.push_str("</div>\n"); // End stats-container
+
+    let labels: Vec<String> = messages.monthly.iter().map(|b| b.period.clone()).collect();
+    html.push_str("<h3>Features and Fixes per Month</h3>\n");
+    html.push_str(&svg_bar_chart(
+        &labels,
+        &[
+            (
+                "Features",
+                "#27ae60",
+                messages.monthly.iter().map(|b| b.features).collect(),
+            ),
+            (
+                "Fixes",
+                "#e74c3c",
+                messages.monthly.iter().map(|b| b.fixes).collect(),
+            ),
+        ],
+    ));
+
+    html.push_str("<h3>Commit Types</h3>\n");
+    html.push_str("<table>\n");
+    html.push_str("<tr><th>Type</th><th>Commits</th></tr>\n");
+    for (commit_type, count) in &messages.types {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td></tr>\n",
+            html_escape(commit_type),
+            count
+        ));
+    }
+    html.push_str("</table>\n");
+
+    if !messages.top_references.is_empty() {
+        let references: Vec<String> = messages
+            .top_references
+            .iter()
+            .map(|(reference, count)| format!("{} ({})", html_escape(reference), count))
+            .collect();
+        html.push_str(&format!(
+            "<p>Most referenced issues: {}</p>\n",
+            references.join(", ")
         ));
     }
 
+    html.push_str("<h3>Per Month</h3>\n");
+    html.push_str("<table>\n");
+    html.push_str("<tr><th>Month</th><th>Commits</th><th>Features</th><th>Fixes</th><th>Feature/Fix Ratio</th><th>Linked to an Issue</th></tr>\n");
+    for bucket in &messages.monthly {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.1}%</td></tr>\n",
+            bucket.period,
+            bucket.commits,
+            bucket.features,
+            bucket.fixes,
+            format_ratio(bucket.feature_fix_ratio()),
+            percentage(bucket.issue_linked, bucket.commits)
+        ));
+    }
     html.push_str("</table>\n");
     html.push_str("</div>\n"); // End card
 
+    // Releases
+    let releases = &analysis.releases;
+    if !releases.releases.is_empty() {
+        let cadence = &releases.cadence;
+        html.push_str("<div class=\"card\">\n");
+        html.push_str("<h2>Releases</h2>\n");
+        html.push_str("<div class=\"stats-container\">\n");
+        html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{}</div><div class=\"stat-label\">Releases</div></div>\n",
+            cadence.release_count));
+        html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{:.1}</div><div class=\"stat-label\">Avg Days Between</div></div>\n",
+            cadence.avg_days_between));
+        html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{:.1}</div><div class=\"stat-label\">Median Days Between</div></div>\n",
+            cadence.median_days_between));
+        if let Some(days) = cadence.days_since_last_release {
+            html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{:.0}</div><div class=\"stat-label\">Days Since Last Release</div></div>\n",
+                days));
+        }
+        html.push_str("</div>\n"); // End stats-container
+
+        html.push_str("<table>\n");
+        html.push_str("<tr><th>Tag</th><th>Date</th><th>Commits</th><th>Contributors</th><th>Files Changed</th><th>Lines Added</th><th>Lines Removed</th><th>Types</th></tr>\n");
+        for release in releases
+            .releases
+            .iter()
+            .rev()
+            .chain(releases.unreleased.iter())
+        {
+            let types: Vec<String> = release
+                .types
+                .iter()
+                .map(|(commit_type, count)| format!("{} {}", html_escape(commit_type), count))
+                .collect();
+            let contributors: Vec<String> = release
+                .contributors
+                .iter()
+                .map(|(name, _)| html_escape(name))
+                .collect();
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                html_escape(&release.tag),
+                time_zone.format(&release.date),
+                release.commit_count,
+                contributors.join(", "),
+                release.files_changed,
+                release.lines_added,
+                release.lines_removed,
+                types.join(", ")
+            ));
+        }
+        html.push_str("</table>\n");
+        html.push_str("</div>\n"); // End card
+    }
+
+    // Commit sizes
+    let sizes = &analysis.commit_sizes;
+    html.push_str("<div class=\"card\">\n");
+    html.push_str("<h2>Commit Sizes</h2>\n");
+    html.push_str("<table>\n");
+    html.push_str("<tr><th></th><th>Median</th><th>P90</th><th>Max</th></tr>\n");
+    for (label, distribution) in [
+        ("Files Changed", &sizes.files_changed),
+        ("Lines Added", &sizes.lines_added),
+        ("Lines Removed", &sizes.lines_removed),
+    ] {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            label, distribution.median, distribution.p90, distribution.max
+        ));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h3>Largest Commits</h3>\n");
+    html.push_str("<table>\n");
+    html.push_str("<tr><th>Commit</th><th>Subject</th><th>Author</th><th>Date</th><th>Files Changed</th><th>Lines Added</th><th>Lines Removed</th></tr>\n");
+    for commit in &sizes.largest_commits {
+        html.push_str(&format!(
+            "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            short_id(&commit.id),
+            html_escape(&commit.subject),
+            html_escape(&commit.author),
+            time_zone.format(&commit.date),
+            commit.files_changed,
+            commit.lines_added,
+            commit.lines_removed
+        ));
+    }
+    html.push_str("</table>\n");
+    html.push_str("</div>\n"); // End card
+
+    // Blob history
+    let blob_history = &analysis.blob_history;
+    html.push_str("<div class=\"card\">\n");
+    html.push_str("<h2>Largest Blobs in History</h2>\n");
+    html.push_str(&format!(
+        "<p>{} blobs committed across all refs, {} in total. Deleted blobs still make every clone larger.</p>\n",
+        blob_history.blob_count,
+        format_size(blob_history.total_size)
+    ));
+    html.push_str("<table>\n");
+    html.push_str("<tr><th>Path</th><th>Size</th><th>Introduced In</th><th>Binary</th><th>At HEAD</th></tr>\n");
+    for blob in &blob_history.largest_blobs {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td><code>{}</code></td><td>{}</td><td>{}</td></tr>\n",
+            relative_path(&analysis.repo_path, &blob.path),
+            format_size(blob.size),
+            short_id(&blob.commit_id),
+            if blob.is_binary { "Yes" } else { "No" },
+            if blob.at_head { "Yes" } else { "No" }
+        ));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h3>History Size by Extension</h3>\n");
+    html.push_str("<table>\n");
+    html.push_str("<tr><th>Extension</th><th>Size</th><th>Blobs</th></tr>\n");
+    for (extension, size, count) in &blob_history.size_by_extension {
+        html.push_str(&format!(
+            "<tr><td>.{}</td><td>{}</td><td>{}</td></tr>\n",
+            extension,
+            format_size(*size),
+            count
+        ));
+    }
+    html.push_str("</table>\n");
+    html.push_str("</div>\n"); // End card
+
+    // Signatures
+    let signatures = &analysis.signatures;
+    html.push_str("<div class=\"card\">\n");
+    html.push_str("<h2>Commit Signatures</h2>\n");
+    html.push_str("<div class=\"stats-container\">\n");
+    html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{:.1}%</div><div class=\"stat-label\">Signed Commits</div></div>\n",
+        percentage(signatures.signed_count, signatures.commit_count)));
+    for (kind, count) in &signatures.kinds {
+        html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{}</div><div class=\"stat-label\">{} Signed</div></div>\n",
+            count, kind));
+    }
+    html.push_str("</div>\n"); // End stats-container
+
+    let labels: Vec<String> = signatures
+        .monthly
+        .iter()
+        .map(|b| b.period.clone())
+        .collect();
+    html.push_str("<h3>Signed and Unsigned Commits per Month</h3>\n");
+    html.push_str(&svg_bar_chart(
+        &labels,
+        &[
+            (
+                "Signed",
+                "#27ae60",
+                signatures.monthly.iter().map(|b| b.signed).collect(),
+            ),
+            (
+                "Unsigned",
+                "#e74c3c",
+                signatures
+                    .monthly
+                    .iter()
+                    .map(|b| b.commits - b.signed)
+                    .collect(),
+            ),
+        ],
+    ));
+
+    html.push_str("<h3>Per Contributor</h3>\n");
+    html.push_str("<table>\n");
+    html.push_str("<tr><th>Author</th><th>Commits</th><th>Signed</th><th>Share</th></tr>\n");
+    for (name, signed, total) in &signatures.contributors {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.1}%</td></tr>\n",
+            html_escape(name),
+            total,
+            signed,
+            percentage(*signed, *total)
+        ));
+    }
+    html.push_str("</table>\n");
+    html.push_str("</div>\n"); // End card
+
+    // Branches
+    let branches = &analysis.branches;
+    html.push_str("<div class=\"card\">\n");
+    html.push_str("<h2>Branches</h2>\n");
+    html.push_str(&format!(
+        "<p>Compared with the default branch <strong>{}</strong>. Unmerged branches without commits for {} days are stale.</p>\n",
+        html_escape(branches.default_branch.as_deref().unwrap_or("Unknown")),
+        branches.stale_days
+    ));
+    html.push_str("<table>\n");
+    html.push_str("<tr><th>Branch</th><th>Last Commit</th><th>Author</th><th>Ahead</th><th>Behind</th><th>Status</th></tr>\n");
+    for branch in &branches.branches {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            html_escape(&branch.name),
+            time_zone.format(&branch.last_commit),
+            html_escape(&branch.last_author),
+            branch.ahead,
+            branch.behind,
+            branch_status(branches, branch)
+        ));
+    }
+    html.push_str("</table>\n");
+    html.push_str("</div>\n"); // End card
+
+    // Merges
+    let merges = &analysis.merges;
+    html.push_str("<div class=\"card\">\n");
+    html.push_str("<h2>Merges</h2>\n");
+    html.push_str("<div class=\"stats-container\">\n");
+    html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{}</div><div class=\"stat-label\">Merge Commits</div></div>\n",
+        merges.merge_count));
+    html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{:.2}</div><div class=\"stat-label\">Merges per Week</div></div>\n",
+        merges.merges_per_week));
+    html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{:.1}</div><div class=\"stat-label\">Avg Branch Lifetime (days)</div></div>\n",
+        merges.avg_branch_lifetime_days));
+    html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{:.1}</div><div class=\"stat-label\">Commits per Merged Branch</div></div>\n",
+        merges.avg_commits_per_branch));
+    html.push_str("</div>\n"); // End stats-container
+
+    if !merges.weekly.is_empty() {
+        let labels: Vec<String> = merges.weekly.iter().map(|(week, _)| week.clone()).collect();
+        html.push_str("<h3>Merges per Week</h3>\n");
+        html.push_str(&svg_bar_chart(
+            &labels,
+            &[(
+                "Merges",
+                "#8e44ad",
+                merges.weekly.iter().map(|(_, count)| *count).collect(),
+            )],
+        ));
+    }
+    html.push_str("</div>\n"); // End card
+
+    // Hotspots
+    html.push_str("<div class=\"card\">\n");
+    html.push_str("<h2>Hotspots</h2>\n");
+    html.push_str("<p>Files that are both complex and frequently changed, the best candidates for refactoring.</p>\n");
+    html.push_str("<table>\n");
+    html.push_str("<tr><th>File</th><th>Score</th><th>Complexity</th><th>Commits</th><th>Lines Changed</th><th>Change Frequency</th></tr>\n");
+
+    for hotspot in &analysis.hotspots {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{:.2}<div class=\"progress-bar\"><div class=\"progress-fill\" style=\"width: {:.0}%\"></div></div></td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td></tr>\n",
+            hotspot.path.display(),
+            hotspot.score,
+            hotspot.score * 100.0,
+            hotspot.complexity,
+            hotspot.commit_count,
+            hotspot.lines_changed,
+            hotspot.change_frequency
+        ));
+    }
+
+    html.push_str("</table>\n");
+    html.push_str("</div>\n"); // End card
+
+    // Bus Factor
+    let bus_factor = &analysis.bus_factor;
+    html.push_str("<div class=\"card\">\n");
+    html.push_str("<h2>Bus Factor</h2>\n");
+    html.push_str("<table>\n");
+    html.push_str("<tr><th>Scope</th><th>Files</th><th>Bus Factor</th><th>Key People</th></tr>\n");
+    html.push_str(&format!(
+        "<tr><td>Repository</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+        bus_factor.repository.file_count,
+        bus_factor.repository.bus_factor,
+        html_escape(&bus_factor.repository.key_people.join(", "))
+    ));
+
+    for (path, dir_factor) in &bus_factor.directories {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            path.display(),
+            dir_factor.file_count,
+            dir_factor.bus_factor,
+            html_escape(&dir_factor.key_people.join(", "))
+        ));
+    }
+
+    html.push_str("</table>\n");
+
+    if !bus_factor.orphaned_files.is_empty() {
+        html.push_str(&format!(
+            "<h3>Files Owned Only by Authors Inactive for {} Months</h3>\n",
+            bus_factor.inactive_months
+        ));
+        html.push_str("<table>\n");
+        html.push_str("<tr><th>File</th><th>Owners</th><th>Last Active</th></tr>\n");
+
+        for orphan in &bus_factor.orphaned_files {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                orphan.path.display(),
+                html_escape(&orphan.owners.join(", ")),
+                time_zone.format_opt(orphan.last_active.as_ref())
+            ));
+        }
+
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("</div>\n"); // End card
+
+    // Change Coupling
+    html.push_str("<div class=\"card\">\n");
+    html.push_str("<h2>Change Coupling</h2>\n");
+
+    for (title, pairs) in [
+        ("Files", &analysis.change_coupling.file_pairs),
+        ("Directories", &analysis.change_coupling.directory_pairs),
+    ] {
+        html.push_str(&format!("<h3>{}</h3>\n", title));
+        html.push_str("<table>\n");
+        html.push_str("<tr><th>First</th><th>Second</th><th>Shared Commits</th><th>Support</th><th>Confidence (first &rarr; second)</th><th>Confidence (second &rarr; first)</th></tr>\n");
+
+        for pair in pairs {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.1}%</td><td>{:.0}%</td><td>{:.0}%</td></tr>\n",
+                pair.first.display(),
+                pair.second.display(),
+                pair.co_changes,
+                pair.support * 100.0,
+                pair.confidence_first_to_second * 100.0,
+                pair.confidence_second_to_first * 100.0
+            ));
+        }
+
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("</div>\n"); // End card
+
+    // Code Ownership
+    if let Some(ownership) = &analysis.ownership {
+        html.push_str("<div class=\"card\">\n");
+        html.push_str("<h2>Code Ownership</h2>\n");
+
+        for (title, entries) in [
+            ("Directories", &ownership.directories),
+            ("Files", &ownership.files),
+        ] {
+            html.push_str(&format!("<h3>{}</h3>\n", title));
+            html.push_str("<table>\n");
+            html.push_str(
+                "<tr><th>Path</th><th>Lines</th><th>Primary Owner</th><th>Ownership</th></tr>\n",
+            );
+
+            for (path, owner) in sorted_by_lines(entries).iter().take(10) {
+                if let Some((name, percentage)) = owner.primary_owner() {
+                    html.push_str(&format!(
+                        "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.1}%<div class=\"progress-bar\"><div class=\"progress-fill\" style=\"width: {:.1}%\"></div></div></td></tr>\n",
+                        path.display(),
+                        owner.total_lines,
+                        html_escape(name),
+                        percentage,
+                        percentage
+                    ));
+                }
+            }
+
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("</div>\n"); // End card
+    }
+
+    // Components, with the details of each one folded away
+    if !analysis.components.is_empty() {
+        html.push_str("<div class=\"card\">\n");
+        html.push_str("<h2>Components</h2>\n");
+        html.push_str("<table>\n");
+        html.push_str("<tr><th>Component</th><th>Kind</th><th>Path</th><th>Files</th><th>Lines</th><th>Commits</th><th>Lines Changed</th><th>Avg Complexity</th><th>Contributors</th></tr>\n");
+        for (index, component) in analysis.components.iter().enumerate() {
+            html.push_str(&format!(
+                "<tr><td><a href=\"#component-{}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>+{} -{}</td><td>{:.2}</td><td>{}</td></tr>\n",
+                index,
+                html_escape(&component.name),
+                component.kind.name(),
+                relative_path(&analysis.repo_path, &component.path),
+                component.file_count,
+                component.total_lines,
+                component.commit_count,
+                component.lines_added,
+                component.lines_removed,
+                component.avg_complexity,
+                component.contributors.len()
+            ));
+        }
+        html.push_str("</table>\n");
+
+        for (index, component) in analysis.components.iter().enumerate() {
+            html.push_str(&format!(
+                "<details id=\"component-{}\">\n<summary><strong>{}</strong> ({})</summary>\n",
+                index,
+                html_escape(&component.name),
+                relative_path(&analysis.repo_path, &component.path)
+            ));
+
+            html.push_str("<h3>Languages</h3>\n");
+            html.push_str("<table>\n");
+            html.push_str("<tr><th>Language</th><th>Lines</th><th>Percentage</th></tr>\n");
+            for (language, lines) in sorted_language_lines(&component.language_lines) {
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{:.1}%</td></tr>\n",
+                    language,
+                    lines,
+                    percentage(lines, component.total_lines)
+                ));
+            }
+            html.push_str("</table>\n");
+
+            html.push_str("<h3>Top Contributors</h3>\n");
+            html.push_str("<table>\n");
+            html.push_str("<tr><th>Name</th><th>Commits</th></tr>\n");
+            for (name, commits) in component.contributors.iter().take(top_contributors) {
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td></tr>\n",
+                    html_escape(name),
+                    commits
+                ));
+            }
+            html.push_str("</table>\n");
+
+            html.push_str("<h3>Most Complex Files</h3>\n");
+            html.push_str("<table>\n");
+            html.push_str("<tr><th>File</th><th>Complexity</th></tr>\n");
+            for (path, complexity) in &component.complex_files {
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td></tr>\n",
+                    relative_path(&analysis.repo_path, path),
+                    complexity
+                ));
+            }
+            html.push_str("</table>\n");
+
+            html.push_str("<h3>Most Changed Files</h3>\n");
+            html.push_str("<table>\n");
+            html.push_str("<tr><th>File</th><th>Commits</th></tr>\n");
+            for (path, commits) in &component.changed_files {
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td></tr>\n",
+                    relative_path(&analysis.repo_path, path),
+                    commits
+                ));
+            }
+            html.push_str("</table>\n");
+
+            html.push_str("</details>\n");
+        }
+
+        html.push_str("</div>\n"); // End card
+    }
+
+    // Submodules, each analyzed one linking to its own report
+    if !analysis.submodules.is_empty() {
+        html.push_str("<div class=\"card\">\n");
+        html.push_str("<h2>Submodules</h2>\n");
+        html.push_str("<table>\n");
+        html.push_str("<tr><th>Name</th><th>Path</th><th>URL</th><th>Pinned Commit</th><th>Report</th></tr>\n");
+
+        let stem = file_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "report".to_string());
+        for (index, entry) in analysis.submodules.iter().enumerate() {
+            let submodule = &entry.submodule;
+            let report_link = match &entry.analysis {
+                Some(nested) => {
+                    // Submodule names come from .gitmodules, so the file name is
+                    // built from the index and a slug that cannot leave the directory
+                    let nested_path = file_path.with_file_name(format!(
+                        "{}-submodule-{}-{}.html",
+                        stem,
+                        index + 1,
+                        file_name_slug(&submodule.name)
+                    ));
+                    let nested_path =
+                        generate_html_report(nested, top_contributors, time_zone, nested_path)?;
+                    format!(
+                        "<a href=\"{0}\">{0}</a>",
+                        nested_path
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_default()
+                    )
+                }
+                None if !submodule.initialized => "Not checked out".to_string(),
+                None => "-".to_string(),
+            };
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td><code>{}</code></td><td>{}</td></tr>\n",
+                html_escape(&submodule.name),
+                html_escape(&relative_path(&analysis.repo_path, &submodule.path)),
+                html_escape(submodule.url.as_deref().unwrap_or("")),
+                submodule
+                    .pinned_commit
+                    .as_deref()
+                    .map(short_id)
+                    .unwrap_or(""),
+                report_link
+            ));
+        }
+
+        html.push_str("</table>\n");
+        html.push_str("</div>\n"); // End card
+    }
+
     // Footer
     html.push_str("<div style=\"text-align: center; margin-top: 30px; color: #7f8c8d;\">\n");
     html.push_str("<p>Generated by Repository Analyzer</p>\n");
@@ -635,7 +2753,6 @@ fn generate_html_report(analysis: &RepositoryAnalysis, top_contributors: usize)
     html.push_str("</body>\n</html>");
 
     // Write to file
-    let file_path = PathBuf::from("report.html");
     let mut file = File::create(&file_path).context("Failed to create HTML report file")?;
     file.write_all(html.as_bytes())
         .context("Failed to write HTML report")?;
@@ -643,3 +2760,652 @@ fn generate_html_report(analysis: &RepositoryAnalysis, top_contributors: usize)
     println!("HTML report saved to {}", file_path.display());
     Ok(file_path)
 }
+
+/// Starts an HTML page with the shared styles and the given title
+fn push_html_head(html: &mut String, title: &str) {
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+    html.push_str("<meta charset=\"UTF-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n");
+    html.push_str(&format!("<title>{}</title>\n", title));
+    html.push_str("<style>\n");
+    html.push_str("body { font-family: Arial, sans-serif; line-height: 1.6; color: #333; max-width: 1200px; margin: 0 auto; padding: 20px; }\n");
+    html.push_str("h1, h2, h3 { color: #2c3e50; }\n");
+    html.push_str("table { border-collapse: collapse; width: 100%; margin-bottom: 20px; }\n");
+    html.push_str("th, td { text-align: left; padding: 12px; border-bottom: 1px solid #ddd; }\n");
+    html.push_str("th { background-color: #f2f2f2; }\n");
+    html.push_str("tr:hover { background-color: #f5f5f5; }\n");
+    html.push_str(".card { background: white; border-radius: 5px; box-shadow: 0 2px 5px rgba(0,0,0,0.1); padding: 20px; margin-bottom: 20px; }\n");
+    html.push_str(".stat { font-size: 24px; font-weight: bold; color: #3498db; }\n");
+    html.push_str(".stat-label { font-size: 14px; color: #7f8c8d; }\n");
+    html.push_str(
+        ".stats-container { display: flex; flex-wrap: wrap; gap: 20px; margin-bottom: 20px; }\n",
+    );
+    html.push_str(".stat-box { flex: 1; min-width: 150px; background: #f8f9fa; padding: 15px; border-radius: 5px; text-align: center; }\n");
+    html.push_str(".progress-bar { height: 10px; background: #ecf0f1; border-radius: 5px; margin-top: 5px; overflow: hidden; }\n");
+    html.push_str(".progress-fill { height: 100%; background: #3498db; }\n");
+    html.push_str(".tabs { display: flex; margin-bottom: 20px; }\n");
+    html.push_str(".tab { padding: 10px 20px; cursor: pointer; background: #f2f2f2; border-radius: 5px 5px 0 0; }\n");
+    html.push_str(".tab.active { background: #3498db; color: white; }\n");
+    html.push_str(".tab-content { display: none; }\n");
+    html.push_str(".tab-content.active { display: block; }\n");
+    html.push_str("</style>\n");
+    html.push_str("</head>\n<body>\n");
+}
+
+/// Write the organization report of a batch analysis. Text reports are printed
+/// to the console and return no path
+pub fn generate_org_report(
+    org: &OrgAnalysis,
+    format: &str,
+    top_contributors: usize,
+    time_zone: DisplayTimeZone,
+    file_path: PathBuf,
+) -> Result<Option<PathBuf>> {
+    match format.to_lowercase().as_str() {
+        "json" => {
+            println!("Generating JSON organization report...");
+            let report = OrgJsonReport {
+                repository_count: org.repositories.len(),
+                failure_count: org.failures.len(),
+                total_lines: org.total_lines,
+                code_lines: org.code_lines,
+                commit_count: org.commit_count,
+                language_lines: sorted_language_lines(&org.language_lines)
+                    .into_iter()
+                    .map(|(language, lines)| OrgLanguageInfo {
+                        language: language.clone(),
+                        lines,
+                        percentage: percentage(lines, org.total_lines),
+                    })
+                    .collect(),
+                repositories: org
+                    .repositories
+                    .iter()
+                    .map(|repository| OrgRepositoryInfo {
+                        source: repository.source.clone(),
+                        report: repository.report_path.display().to_string(),
+                        file_count: repository.file_count,
+                        total_lines: repository.total_lines,
+                        code_lines: repository.code_lines,
+                        commit_count: repository.commit_count,
+                        contributor_count: repository.contributor_count,
+                        avg_complexity: repository.avg_complexity,
+                        total_complexity: repository.total_complexity,
+                        last_activity: repository.last_activity.map(|last| last.to_rfc3339()),
+                    })
+                    .collect(),
+                contributors: org
+                    .contributors
+                    .iter()
+                    .take(top_contributors)
+                    .map(|contributor| OrgContributorInfo {
+                        name: contributor.name.clone(),
+                        email: contributor.email.clone(),
+                        repositories: contributor.repositories,
+                        commit_count: contributor.commit_count,
+                    })
+                    .collect(),
+                failures: org
+                    .failures
+                    .iter()
+                    .map(|(source, error)| OrgFailureInfo {
+                        source: source.clone(),
+                        error: error.clone(),
+                    })
+                    .collect(),
+            };
+
+            let file = File::create(&file_path).context("Failed to create JSON report file")?;
+            serde_json::to_writer_pretty(file, &report).context("Failed to write JSON report")?;
+            println!("JSON organization report saved to {}", file_path.display());
+            Ok(Some(file_path))
+        }
+        "html" => {
+            generate_org_html_report(org, top_contributors, time_zone, &file_path)?;
+            Ok(Some(file_path))
+        }
+        _ => {
+            generate_org_text_report(org, top_contributors, time_zone);
+            Ok(None)
+        }
+    }
+}
+
+fn generate_org_text_report(
+    org: &OrgAnalysis,
+    top_contributors: usize,
+    time_zone: DisplayTimeZone,
+) {
+    println!("\n{}", "Organization Analysis Report".yellow().bold());
+    println!("{}", "============================".yellow());
+
+    println!("\n{}", "General Information:".cyan().bold());
+    println!("Repositories Analyzed: {}", org.repositories.len());
+    println!("Repositories Failed: {}", org.failures.len());
+    println!("Total Lines of Code: {}", org.total_lines);
+    println!("Code Lines: {}", org.code_lines);
+    println!("Total Commits: {}", org.commit_count);
+    println!("Contributors: {}", org.contributors.len());
+
+    println!("\n{}", "Lines by Language:".cyan().bold());
+    for (language, lines) in sorted_language_lines(&org.language_lines) {
+        println!(
+            "{}: {} lines ({:.1}%)",
+            language,
+            lines,
+            percentage(lines, org.total_lines)
+        );
+    }
+
+    println!("\n{}", "Largest Repositories:".cyan().bold());
+    for (i, repository) in largest_repositories(org)
+        .iter()
+        .enumerate()
+        .take(top_contributors)
+    {
+        println!(
+            "{}. {} - {} lines, {} files, {} commits (last activity: {})",
+            i + 1,
+            repository.source,
+            repository.total_lines,
+            repository.file_count,
+            repository.commit_count,
+            time_zone.format_opt(repository.last_activity.as_ref())
+        );
+    }
+
+    println!("\n{}", "Most Complex Repositories:".cyan().bold());
+    for (i, repository) in most_complex_repositories(org)
+        .iter()
+        .enumerate()
+        .take(top_contributors)
+    {
+        println!(
+            "{}. {} - total complexity {}, average {:.2}",
+            i + 1,
+            repository.source,
+            repository.total_complexity,
+            repository.avg_complexity
+        );
+    }
+
+    println!("\n{}", "Cross-Repository Contributors:".cyan().bold());
+    for (i, contributor) in org.contributors.iter().enumerate().take(top_contributors) {
+        println!(
+            "{}. {} <{}> - {} repositories, {} commits",
+            i + 1,
+            contributor.name,
+            contributor.email,
+            contributor.repositories,
+            contributor.commit_count
+        );
+    }
+
+    println!("\n{}", "Repository Reports:".cyan().bold());
+    for repository in &org.repositories {
+        println!(
+            "- {}: {}",
+            repository.source,
+            repository.report_path.display()
+        );
+    }
+
+    if !org.failures.is_empty() {
+        println!("\n{}", "Failed Repositories:".cyan().bold());
+        for (source, error) in &org.failures {
+            println!("- {}: {}", source, error);
+        }
+    }
+}
+
+fn generate_org_html_report(
+    org: &OrgAnalysis,
+    top_contributors: usize,
+    time_zone: DisplayTimeZone,
+    file_path: &Path,
+) -> Result<()> {
+    println!("Generating HTML organization report...");
+
+    let mut html = String::new();
+    push_html_head(&mut html, "Organization Analysis Report");
+    html.push_str("<h1>Organization Analysis</h1>\n");
+
+    // Overview stats
+    html.push_str("<div class=\"card\">\n");
+    html.push_str("<h2>Overview</h2>\n");
+    html.push_str("<div class=\"stats-container\">\n");
+    for (value, label) in [
+        (org.repositories.len(), "Repositories"),
+        (org.failures.len(), "Failed"),
+        (org.total_lines, "Lines of Code"),
+        (org.commit_count, "Commits"),
+        (org.contributors.len(), "Contributors"),
+    ] {
+        html.push_str(&format!("<div class=\"stat-box\"><div class=\"stat\">{}</div><div class=\"stat-label\">{}</div></div>\n", value, label));
+    }
+    html.push_str("</div>\n"); // End stats-container
+    html.push_str("</div>\n"); // End card
+
+    // Lines by language
+    html.push_str("<div class=\"card\">\n");
+    html.push_str("<h2>Lines by Language</h2>\n");
+    html.push_str("<table>\n");
+    html.push_str("<tr><th>Language</th><th>Lines</th><th>Percentage</th></tr>\n");
+    for (language, lines) in sorted_language_lines(&org.language_lines) {
+        let share = percentage(lines, org.total_lines);
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{:.1}%<div class=\"progress-bar\"><div class=\"progress-fill\" style=\"width: {:.1}%\"></div></div></td></tr>\n",
+            language, lines, share, share
+        ));
+    }
+    html.push_str("</table>\n");
+    html.push_str("</div>\n"); // End card
+
+    // Repositories, each linking to its own report
+    let report_dir = file_path.parent().unwrap_or_else(|| Path::new(""));
+    html.push_str("<div class=\"card\">\n");
+    html.push_str("<h2>Repositories</h2>\n");
+    html.push_str("<table>\n");
+    html.push_str("<tr><th>Repository</th><th>Files</th><th>Lines</th><th>Commits</th><th>Contributors</th><th>Total Complexity</th><th>Avg Complexity</th><th>Last Activity</th></tr>\n");
+    for repository in largest_repositories(org) {
+        let link = repository
+            .report_path
+            .strip_prefix(report_dir)
+            .unwrap_or(&repository.report_path);
+        html.push_str(&format!(
+            "<tr><td><a href=\"{}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{}</td></tr>\n",
+            link.display(),
+            repository.source,
+            repository.file_count,
+            repository.total_lines,
+            repository.commit_count,
+            repository.contributor_count,
+            repository.total_complexity,
+            repository.avg_complexity,
+            time_zone.format_opt(repository.last_activity.as_ref())
+        ));
+    }
+    html.push_str("</table>\n");
+    html.push_str("</div>\n"); // End card
+
+    // Most complex repositories
+    html.push_str("<div class=\"card\">\n");
+    html.push_str("<h2>Most Complex Repositories</h2>\n");
+    html.push_str("<table>\n");
+    html.push_str("<tr><th>Repository</th><th>Total Complexity</th><th>Avg Complexity</th></tr>\n");
+    for repository in most_complex_repositories(org).iter().take(top_contributors) {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{:.2}</td></tr>\n",
+            repository.source, repository.total_complexity, repository.avg_complexity
+        ));
+    }
+    html.push_str("</table>\n");
+    html.push_str("</div>\n"); // End card
+
+    // Contributors active in the most repositories
+    html.push_str("<div class=\"card\">\n");
+    html.push_str("<h2>Cross-Repository Contributors</h2>\n");
+    html.push_str("<table>\n");
+    html.push_str("<tr><th>Name</th><th>Email</th><th>Repositories</th><th>Commits</th></tr>\n");
+    for contributor in org.contributors.iter().take(top_contributors) {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            html_escape(&contributor.name),
+            html_escape(&contributor.email),
+            contributor.repositories,
+            contributor.commit_count
+        ));
+    }
+    html.push_str("</table>\n");
+    html.push_str("</div>\n"); // End card
+
+    if !org.failures.is_empty() {
+        html.push_str("<div class=\"card\">\n");
+        html.push_str("<h2>Failed Repositories</h2>\n");
+        html.push_str("<table>\n");
+        html.push_str("<tr><th>Repository</th><th>Error</th></tr>\n");
+        for (source, error) in &org.failures {
+            html.push_str(&format!("<tr><td>{}</td><td>{}</td></tr>\n", source, error));
+        }
+        html.push_str("</table>\n");
+        html.push_str("</div>\n"); // End card
+    }
+
+    // Footer
+    html.push_str("<div style=\"text-align: center; margin-top: 30px; color: #7f8c8d;\">\n");
+    html.push_str("<p>Generated by Repository Analyzer</p>\n");
+    html.push_str("</div>\n");
+
+    html.push_str("</body>\n</html>");
+
+    let mut file = File::create(file_path).context("Failed to create HTML report file")?;
+    file.write_all(html.as_bytes())
+        .context("Failed to write HTML report")?;
+
+    println!("HTML organization report saved to {}", file_path.display());
+    Ok(())
+}
+
+fn sorted_language_lines(language_lines: &HashMap<String, usize>) -> Vec<(&String, usize)> {
+    let mut languages: Vec<(&String, usize)> = language_lines
+        .iter()
+        .map(|(language, lines)| (language, *lines))
+        .collect();
+    languages.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    languages
+}
+
+fn largest_repositories(org: &OrgAnalysis) -> Vec<&RepositorySummary> {
+    let mut repositories: Vec<&RepositorySummary> = org.repositories.iter().collect();
+    repositories.sort_by_key(|repository| std::cmp::Reverse(repository.total_lines));
+    repositories
+}
+
+fn most_complex_repositories(org: &OrgAnalysis) -> Vec<&RepositorySummary> {
+    let mut repositories: Vec<&RepositorySummary> = org.repositories.iter().collect();
+    repositories.sort_by_key(|repository| std::cmp::Reverse(repository.total_complexity));
+    repositories
+}
+
+/// Renders one or more series as grouped vertical bars, labelling at most
+/// twelve of the periods on the x axis
+fn svg_bar_chart(labels: &[String], series: &[(&str, &str, Vec<usize>)]) -> String {
+    let (width, height, margin) = (1100.0, 220.0, 30.0);
+    let max_value = series
+        .iter()
+        .flat_map(|(_, _, values)| values.iter().copied())
+        .max()
+        .unwrap_or(0)
+        .max(1) as f64;
+
+    let slot = (width - margin) / labels.len().max(1) as f64;
+    let bar_width = (slot * 0.8 / series.len().max(1) as f64).max(1.0);
+    let label_step = labels.len() / 12 + 1;
+
+    let mut svg = format!(
+        "<svg viewBox=\"0 0 {} {}\" width=\"100%\" role=\"img\">\n",
+        width,
+        height + margin
+    );
+    svg.push_str(&format!(
+        "<text x=\"0\" y=\"12\" font-size=\"11\" fill=\"#7f8c8d\">{}</text>\n",
+        max_value
+    ));
+
+    for (i, label) in labels.iter().enumerate() {
+        let x = margin + i as f64 * slot;
+        for (j, (name, color, values)) in series.iter().enumerate() {
+            let value = values.get(i).copied().unwrap_or(0);
+            let bar_height = value as f64 / max_value * (height - 20.0);
+            svg.push_str(&format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"><title>{} {}: {}</title></rect>\n",
+                x + j as f64 * bar_width,
+                height - bar_height,
+                bar_width,
+                bar_height,
+                color,
+                label,
+                name,
+                value
+            ));
+        }
+        if i % label_step == 0 {
+            svg.push_str(&format!(
+                "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"11\" fill=\"#7f8c8d\">{}</text>\n",
+                x,
+                height + 18.0,
+                label
+            ));
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Renders commits per weekday and hour as circles sized by commit count
+fn svg_punch_card(punch_card: &[[usize; 24]; 7]) -> String {
+    let (cell, left, top) = (40.0, 90.0, 20.0);
+    let max_count = punch_card
+        .iter()
+        .flatten()
+        .copied()
+        .max()
+        .unwrap_or(0)
+        .max(1) as f64;
+
+    let mut svg = format!(
+        "<svg viewBox=\"0 0 {} {}\" width=\"100%\" role=\"img\">\n",
+        left + 24.0 * cell,
+        top + 7.0 * cell
+    );
+
+    for hour in 0..24 {
+        svg.push_str(&format!(
+            "<text x=\"{:.1}\" y=\"12\" font-size=\"11\" text-anchor=\"middle\" fill=\"#7f8c8d\">{}</text>\n",
+            left + (hour as f64 + 0.5) * cell,
+            hour
+        ));
+    }
+
+    for (day, hours) in punch_card.iter().enumerate() {
+        let y = top + (day as f64 + 0.5) * cell;
+        svg.push_str(&format!(
+            "<text x=\"0\" y=\"{:.1}\" font-size=\"12\" fill=\"#2c3e50\">{}</text>\n",
+            y + 4.0,
+            WEEKDAYS[day]
+        ));
+
+        for (hour, count) in hours.iter().enumerate() {
+            if *count == 0 {
+                continue;
+            }
+            let radius = (*count as f64 / max_count).sqrt() * cell * 0.45;
+            svg.push_str(&format!(
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"#3498db\"><title>{} {}:00 - {} commits</title></circle>\n",
+                left + (hour as f64 + 0.5) * cell,
+                y,
+                radius,
+                WEEKDAYS[day],
+                hour,
+                count
+            ));
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Writes the change coupling as a Graphviz DOT graph, with one cluster for
+/// files and one for directories and edges weighted by shared commits
+pub fn write_coupling_graph(analysis: &RepositoryAnalysis, file_path: &Path) -> Result<()> {
+    let mut dot = String::new();
+    dot.push_str("graph change_coupling {\n");
+    dot.push_str("  node [shape=box, fontname=\"Arial\"];\n");
+
+    for (name, label, pairs) in [
+        ("files", "Files", &analysis.change_coupling.file_pairs),
+        (
+            "directories",
+            "Directories",
+            &analysis.change_coupling.directory_pairs,
+        ),
+    ] {
+        dot.push_str(&format!("  subgraph cluster_{} {{\n", name));
+        dot.push_str(&format!("    label=\"{}\";\n", label));
+
+        // Node ids are prefixed so a path can appear in both clusters
+        let mut nodes: Vec<String> = pairs
+            .iter()
+            .flat_map(|pair| [&pair.first, &pair.second])
+            .map(|path| dot_escape(&relative_path(&analysis.repo_path, path)))
+            .collect();
+        nodes.sort();
+        nodes.dedup();
+        for node in &nodes {
+            dot.push_str(&format!(
+                "    \"{}:{}\" [label=\"{}\"];\n",
+                name, node, node
+            ));
+        }
+
+        for pair in pairs {
+            let first = dot_escape(&relative_path(&analysis.repo_path, &pair.first));
+            let second = dot_escape(&relative_path(&analysis.repo_path, &pair.second));
+            dot.push_str(&format!(
+                "    \"{}:{}\" -- \"{}:{}\" [label=\"{}\", penwidth={:.1}];\n",
+                name,
+                first,
+                name,
+                second,
+                pair.co_changes,
+                1.0 + pair
+                    .confidence_first_to_second
+                    .max(pair.confidence_second_to_first)
+                    * 4.0
+            ));
+        }
+
+        dot.push_str("  }\n");
+    }
+
+    dot.push_str("}\n");
+
+    let mut file = File::create(file_path).context("Failed to create coupling graph file")?;
+    file.write_all(dot.as_bytes())
+        .context("Failed to write coupling graph")?;
+
+    println!("Change coupling graph saved to {}", file_path.display());
+    Ok(())
+}
+
+/// Escapes text for use inside a double-quoted DOT string
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Number of files and lines of each class of vendored, generated and minified files
+fn classified_totals(analysis: &RepositoryAnalysis) -> Vec<(FileClass, usize, usize)> {
+    [
+        FileClass::Vendored,
+        FileClass::Generated,
+        FileClass::Minified,
+    ]
+    .into_iter()
+    .filter_map(|class| {
+        let files: Vec<_> = analysis
+            .classified_files
+            .iter()
+            .filter(|file| file.class == class)
+            .collect();
+        if files.is_empty() {
+            return None;
+        }
+        Some((
+            class,
+            files.len(),
+            files.iter().map(|file| file.lines).sum(),
+        ))
+    })
+    .collect()
+}
+
+fn branch_status(branches: &BranchHealth, branch: &git::BranchInfo) -> String {
+    let mut status = Vec::new();
+    if branch.is_default {
+        status.push("default");
+    } else if branch.merged {
+        status.push("merged");
+    }
+    if branches.stale.contains(&branch.name) {
+        status.push("stale");
+    }
+    if branches.diverged.contains(&branch.name) {
+        status.push("diverged");
+    }
+    if branch.remote {
+        status.push("remote");
+    }
+    status.join(", ")
+}
+
+fn format_size(bytes: u64) -> String {
+    let bytes = bytes as f64;
+    if bytes >= 1024.0 * 1024.0 * 1024.0 {
+        format!("{:.2} GB", bytes / (1024.0 * 1024.0 * 1024.0))
+    } else if bytes >= 1024.0 * 1024.0 {
+        format!("{:.2} MB", bytes / (1024.0 * 1024.0))
+    } else if bytes >= 1024.0 {
+        format!("{:.2} KB", bytes / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// Abbreviated commit hash, as shown by `git log --oneline`
+fn short_id(id: &str) -> &str {
+    &id[..id.len().min(7)]
+}
+
+fn percentage(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64 * 100.0
+    }
+}
+
+fn format_ratio(ratio: Option<f64>) -> String {
+    ratio
+        .map(|ratio| format!("{:.2}", ratio))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Escapes text taken from commits, refs and signatures for use in HTML
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Letters, digits, '_' and '-' of a name, with every other run of characters
+/// replaced by a single '-', for use in file names
+fn file_name_slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+            slug.push(c);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+fn relative_path(repo_path: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(repo_path).unwrap_or(path);
+    if relative.as_os_str().is_empty() {
+        ".".to_string()
+    } else {
+        relative.display().to_string()
+    }
+}
+
+/// Orders ownership entries by the number of lines they cover, largest first
+fn sorted_by_lines(entries: &HashMap<PathBuf, Ownership>) -> Vec<(&PathBuf, &Ownership)> {
+    let mut sorted: Vec<(&PathBuf, &Ownership)> = entries.iter().collect();
+    sorted.sort_by(|(a_path, a), (b_path, b)| {
+        b.total_lines.cmp(&a.total_lines).then(a_path.cmp(b_path))
+    });
+    sorted
+}
@@ -0,0 +1,360 @@
+//! Clones from a local HTTP server that requires Basic authentication and
+//! serves the repository through `git http-backend`, to check which
+//! credentials are offered over HTTP and in what order. The order of the SSH
+//! agent and key files is covered by the unit tests in `git.rs`, as no SSH
+//! server is available to the tests.
+
+use git2::{ConfigLevel, Repository, Signature};
+use repo_analyzer::git::{self, CloneAuth, CloneOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+
+const HELPER_PASSWORD: &str = "from-helper";
+
+/// A scratch directory unique to this test, removed when dropped
+struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!(
+            "repo-analyzer-test-{}-{}",
+            std::process::id(),
+            name
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        ScratchDir(dir)
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(&self.0).unwrap_or(());
+    }
+}
+
+/// A test's scratch directory, with libgit2 configured to use a credential
+/// helper that always answers alice / HELPER_PASSWORD
+struct Fixture {
+    dir: ScratchDir,
+    _config: MutexGuard<'static, ()>,
+}
+
+/// libgit2's config search paths are process-wide, so every test in this
+/// binary must go through `configure_credential_helper`, which holds this lock
+/// for the whole test
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+/// Points libgit2's global config at a file with the credential helper and
+/// its system and XDG configs at an empty directory, away from any user or
+/// system config, until the returned fixture is dropped
+fn configure_credential_helper(name: &str) -> Fixture {
+    let config = CONFIG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = ScratchDir::new(name);
+    let global = dir.0.join("config");
+    let empty = dir.0.join("empty");
+    std::fs::create_dir_all(&global).unwrap();
+    std::fs::create_dir_all(&empty).unwrap();
+    std::fs::write(
+        global.join(".gitconfig"),
+        format!(
+            "[credential]\n\thelper = \"!f() {{ echo username=alice; echo password={}; }}; f\"\n",
+            HELPER_PASSWORD
+        ),
+    )
+    .unwrap();
+    // Safe as long as no other thread uses libgit2 meanwhile, which CONFIG_LOCK
+    // guarantees for the tests of this binary
+    unsafe {
+        git2::opts::set_search_path(ConfigLevel::Global, &global).unwrap();
+        git2::opts::set_search_path(ConfigLevel::XDG, &empty).unwrap();
+        git2::opts::set_search_path(ConfigLevel::System, &empty).unwrap();
+    }
+    Fixture {
+        dir,
+        _config: config,
+    }
+}
+
+/// A bare repository with a single commit, served from `root`
+fn bare_repository(root: &Path) {
+    let repo = Repository::init_bare(root.join("fixture.git")).unwrap();
+    let blob = repo.blob(b"hello\n").unwrap();
+    let mut tree = repo.treebuilder(None).unwrap();
+    tree.insert("README", blob, 0o100644).unwrap();
+    let tree = repo.find_tree(tree.write().unwrap()).unwrap();
+    let signature = Signature::now("Alice", "alice@example.com").unwrap();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "Initial commit",
+        &tree,
+        &[],
+    )
+    .unwrap();
+}
+
+fn base64(input: &str) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut output = String::new();
+    for chunk in input.as_bytes().chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+/// The value of a Basic Authorization header for these credentials
+fn basic(user: &str, password: &str) -> String {
+    format!("Basic {}", base64(&format!("{}:{}", user, password)))
+}
+
+/// Serves `project_root` over HTTP, accepting only `accepted` credentials.
+/// Returns the URL of the fixture and the Authorization header of every
+/// rejected request, None when the request had none
+fn serve(project_root: PathBuf, accepted: String) -> (String, Arc<Mutex<Vec<Option<String>>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/fixture.git", listener.local_addr().unwrap());
+    let rejected = Arc::new(Mutex::new(Vec::new()));
+
+    let recorded = Arc::clone(&rejected);
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => continue,
+            };
+            handle(stream, &project_root, &accepted, &recorded);
+        }
+    });
+
+    (url, rejected)
+}
+
+fn handle(
+    mut stream: TcpStream,
+    project_root: &Path,
+    accepted: &str,
+    rejected: &Mutex<Vec<Option<String>>>,
+) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+        return;
+    }
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let target = parts.next().unwrap_or("").to_string();
+
+    let mut headers: Vec<(String, String)> = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+    }
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    };
+
+    let mut body = Vec::new();
+    if let Some(length) = header("content-length") {
+        body.resize(length.parse().unwrap(), 0);
+        reader.read_exact(&mut body).unwrap();
+    } else if header("transfer-encoding").as_deref() == Some("chunked") {
+        loop {
+            let mut size = String::new();
+            reader.read_line(&mut size).unwrap();
+            let size = usize::from_str_radix(size.trim(), 16).unwrap();
+            let mut chunk = vec![0; size + 2];
+            reader.read_exact(&mut chunk).unwrap();
+            if size == 0 {
+                break;
+            }
+            body.extend_from_slice(&chunk[..size]);
+        }
+    }
+
+    let authorization = header("authorization");
+    if authorization.as_deref() != Some(accepted) {
+        rejected.lock().unwrap().push(authorization);
+        stream
+            .write_all(
+                b"HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Basic realm=\"test\"\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            )
+            .unwrap();
+        return;
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let mut backend = Command::new("git")
+        .arg("http-backend")
+        .env("GIT_PROJECT_ROOT", project_root)
+        .env("GIT_HTTP_EXPORT_ALL", "1")
+        .env("REQUEST_METHOD", &method)
+        .env("PATH_INFO", path)
+        .env("QUERY_STRING", query)
+        .env("CONTENT_TYPE", header("content-type").unwrap_or_default())
+        .env("CONTENT_LENGTH", body.len().to_string())
+        .env(
+            "HTTP_CONTENT_ENCODING",
+            header("content-encoding").unwrap_or_default(),
+        )
+        .env("GIT_PROTOCOL", header("git-protocol").unwrap_or_default())
+        .env("REMOTE_USER", "alice")
+        .env("REMOTE_ADDR", "127.0.0.1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    backend.stdin.take().unwrap().write_all(&body).unwrap();
+    let output = backend.wait_with_output().unwrap();
+
+    // CGI output is headers, a blank line and the body
+    let split = output
+        .stdout
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .map(|index| (index, index + 4))
+        .or_else(|| {
+            output
+                .stdout
+                .windows(2)
+                .position(|window| window == b"\n\n")
+                .map(|index| (index, index + 2))
+        })
+        .unwrap();
+    let cgi_headers = String::from_utf8_lossy(&output.stdout[..split.0]).to_string();
+    let cgi_body = &output.stdout[split.1..];
+
+    let mut status = "200 OK".to_string();
+    let mut response_headers = String::new();
+    for line in cgi_headers.lines() {
+        match line.split_once(':') {
+            Some((name, value)) if name.eq_ignore_ascii_case("status") => {
+                status = value.trim().to_string()
+            }
+            Some(_) => response_headers.push_str(&format!("{}\r\n", line)),
+            None => {}
+        }
+    }
+    let head = format!(
+        "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        response_headers,
+        cgi_body.len()
+    );
+    stream.write_all(head.as_bytes()).unwrap();
+    stream.write_all(cgi_body).unwrap();
+}
+
+fn clone_options(token: &str) -> CloneOptions {
+    CloneOptions {
+        auth: CloneAuth {
+            ssh_agent: true,
+            ssh_keys: vec![PathBuf::from("/nonexistent/id_ed25519")],
+            username: Some("alice".to_string()),
+            token: Some(token.to_string()),
+            credential_helper: true,
+            ..CloneAuth::default()
+        },
+        ..CloneOptions::default()
+    }
+}
+
+#[test]
+fn token_is_tried_before_the_credential_helper() {
+    let fixture = configure_credential_helper("helper-accepted");
+    let root = fixture.dir.0.clone();
+    bare_repository(&root);
+    let (url, rejected) = serve(root.clone(), basic("alice", HELPER_PASSWORD));
+
+    let target = root.join("clone");
+    git::clone_repository(&url, &target, &clone_options("wrong-token")).unwrap();
+
+    // The SSH agent and key file come first but are skipped, as HTTP only
+    // allows a username and password; offering them would fail the clone
+    assert!(target.join("README").is_file());
+    assert_eq!(
+        *rejected.lock().unwrap(),
+        vec![None, Some(basic("alice", "wrong-token"))]
+    );
+}
+
+#[test]
+fn ssh_credentials_are_not_offered_over_http() {
+    let fixture = configure_credential_helper("ssh-only");
+    let root = fixture.dir.0.clone();
+    bare_repository(&root);
+    let (url, rejected) = serve(root.clone(), basic("alice", HELPER_PASSWORD));
+
+    let options = CloneOptions {
+        auth: CloneAuth {
+            ssh_agent: true,
+            ssh_keys: vec![PathBuf::from("/nonexistent/id_ed25519")],
+            ..CloneAuth::default()
+        },
+        ..CloneOptions::default()
+    };
+    let error = git::clone_repository(&url, &root.join("clone"), &options)
+        .map(|_| ())
+        .unwrap_err();
+
+    assert!(
+        format!("{:#}", error).contains("No more credentials to try"),
+        "unexpected error: {:#}",
+        error
+    );
+    assert_eq!(*rejected.lock().unwrap(), vec![None]);
+}
+
+#[test]
+fn clone_fails_once_every_credential_was_rejected() {
+    let fixture = configure_credential_helper("all-rejected");
+    let root = fixture.dir.0.clone();
+    bare_repository(&root);
+    let (url, rejected) = serve(root.clone(), basic("alice", "only-this-works"));
+
+    let target = root.join("clone");
+    let error = git::clone_repository(&url, &target, &clone_options("wrong-token"))
+        .map(|_| ())
+        .unwrap_err();
+
+    assert!(
+        format!("{:#}", error).contains("No more credentials to try"),
+        "unexpected error: {:#}",
+        error
+    );
+    assert_eq!(
+        *rejected.lock().unwrap(),
+        vec![
+            None,
+            Some(basic("alice", "wrong-token")),
+            Some(basic("alice", HELPER_PASSWORD)),
+        ]
+    );
+}